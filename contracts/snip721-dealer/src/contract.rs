@@ -1,6 +1,7 @@
 use cosmwasm_std::{
-    entry_point, to_binary, Addr, BankMsg, Binary, ContractInfo, CosmosMsg, Deps, DepsMut, Env,
-    MessageInfo, Reply, Response, StdError, StdResult, SubMsg, WasmMsg,
+    entry_point, from_binary, to_binary, Addr, Binary, BlockInfo, CanonicalAddr, Coin,
+    ContractInfo, CosmosMsg, Deps, DepsMut, Env, MessageInfo, OverflowError, OverflowOperation,
+    Reply, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
 use cw_migratable_contract_std::execute::{
     add_migration_complete_event_subscriber, register_to_notify_on_migration_complete,
@@ -8,15 +9,39 @@ use cw_migratable_contract_std::execute::{
 };
 use cw_migratable_contract_std::msg::{MigratableExecuteMsg, MigrationListenerExecuteMsg};
 use cw_migratable_contract_std::state::canonicalize;
-use snip721_reference_impl::msg::ExecuteMsg::{ChangeAdmin, MintNft};
-use snip721_reference_impl::msg::{InstantiateConfig, InstantiateMsg as Snip721InstantiateMsg};
+use migration_utils::migration_complete_notifications::{
+    load_failed_migration_complete_notifications, on_migration_complete_notification_reply,
+    retry_failed_migration_complete_notifications, unsubscribe_from_migration_complete_event,
+    MIGRATION_COMPLETE_NOTIFICATION_REPLY_ID_OFFSET,
+};
+use migration_utils::version::{save_contract_version, CONTRACT_VERSION};
+use secret_toolkit::crypto::sha_256;
+use secret_toolkit::permit::{validate, RevokedPermits, TokenPermissions};
+use secret_toolkit::viewing_key::{ViewingKey, ViewingKeyStore};
+use snip721_reference_impl::mint_run::SerialNumber;
+use snip721_reference_impl::msg::ExecuteMsg::{BatchMintNft, ChangeAdmin, MintNft};
+use snip721_reference_impl::msg::{
+    InstantiateConfig, InstantiateMsg as Snip721InstantiateMsg, Mint,
+    QueryAnswer as Snip721QueryAnswer, QueryMsg as Snip721QueryMsg,
+};
 
-use crate::msg::{DealerExecuteMsg, ExecuteMsg, InstantiateMsg, QueryAnswer, QueryMsg};
-use crate::msg_external::MigratableSnip721InstantiateMsg;
+use crate::msg::{
+    DealerExecuteMsg, ExecuteAnswer, ExecuteMsg, InstantiateMsg, Presale, QueryAnswer, QueryMsg,
+    QueryWithPermit, ReceiveMsg, SaleStatus, SaleTime, Snip20Price, Voucher,
+};
+use crate::msg_external::{
+    MigratableSnip721InstantiateMsg, MigrationCompleteEventSubscriptionPolicy, Snip20ExecuteMsg,
+};
+use crate::payees::{canonicalize_payees, load_balances, load_payees, pay_payees, withdraw};
 use crate::state::{
-    PurchasableMetadata, ADMIN, CHILD_SNIP721_ADDRESS, CHILD_SNIP721_CODE_HASH,
-    PURCHASABLE_METADATA, PURCHASE_PRICES,
+    PurchasableMetadata, StoredSnip20Price, ADMIN, ALLOWLIST, CHILD_SNIP721_ADDRESS,
+    CHILD_SNIP721_CODE_HASH, CHILD_SNIP721_MINTER_CONFIRMED, CURRENT_CODE_HASH, HOLD_PROCEEDS,
+    MAX_MINTS_PER_ADDRESS, MAX_SUPPLY, METADATA_POOL, MIGRATION_HISTORY, MINTED_COUNT, MINT_RUN,
+    PAYEES, PENDING_ADMIN, PREFIX_REVOKED_PERMITS, PRESALE, PRESALE_PURCHASE_COUNTS, PRNG_SEED,
+    PURCHASABLE_METADATA, PURCHASE_COUNTS, PURCHASE_PRICES, RANDOM_METADATA_POOL, SALE_END,
+    SALE_START, SNIP20_PRICES, TOKEN_ID_TEMPLATE,
 };
+use crate::version::{PKG_NAME, PKG_VERSION};
 
 const INSTANTIATE_SNIP721_REPLY_ID: u64 = 1u64;
 
@@ -27,12 +52,24 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
-    if msg.prices.is_empty() {
-        return Err(StdError::generic_err("No purchase prices were specified"));
+    let snip20_prices = canonicalize_snip20_prices(deps.as_ref(), msg.snip20_prices)?;
+    validate_prices(&msg.prices, !snip20_prices.is_empty())?;
+    assert_prices_are_positive(&msg.prices)?;
+    if msg.existing_snip721.is_some()
+        && (msg.snip721_code_id.is_some()
+            || msg.snip721_code_hash.is_some()
+            || msg.snip721_label.is_some()
+            || msg.snip721_name.is_some()
+            || msg.snip721_symbol.is_some()
+            || msg.snip721_config.is_some())
+    {
+        return Err(StdError::generic_err(
+            "No snip721 code id, code hash, label, name, symbol or config can be set with existing_snip721",
+        ));
     }
     // instantiate the child snip721 w/ this contract as admin to add this contract to its list of
     // minters. Then set a second msg in Reply to change the admin to true_admin
-    let temp_snip721_admin = env.contract.address;
+    let temp_snip721_admin = env.contract.address.clone();
     let true_admin = match msg.admin {
         Some(admin) => deps.api.addr_validate(admin.as_str())?,
         None => info.sender,
@@ -42,7 +79,48 @@ pub fn instantiate(
         &deps.api.addr_canonicalize(true_admin.as_str())?,
     )?;
     PURCHASE_PRICES.save(deps.storage, &msg.prices)?;
-    CHILD_SNIP721_CODE_HASH.save(deps.storage, &msg.snip721_code_hash)?;
+    SNIP20_PRICES.save(deps.storage, &snip20_prices)?;
+    // register with each SNIP-20 so sending its tokens to this contract calls Receive
+    let register_receive_msgs = snip20_prices
+        .iter()
+        .map(|snip20_price| {
+            Ok(WasmMsg::Execute {
+                contract_addr: deps
+                    .api
+                    .addr_humanize(&snip20_price.token.address)?
+                    .to_string(),
+                code_hash: snip20_price.token.code_hash.clone(),
+                msg: to_binary(&Snip20ExecuteMsg::RegisterReceive {
+                    code_hash: env.contract.code_hash.clone(),
+                    padding: None,
+                })?,
+                funds: vec![],
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    if let Some(payees) = &msg.payees {
+        let payees = canonicalize_payees(deps.as_ref(), payees)?;
+        PAYEES.save(deps.storage, &payees)?;
+    }
+    HOLD_PROCEEDS.save(deps.storage, &msg.hold_proceeds.unwrap_or(false))?;
+    if let Some(max_supply) = msg.max_supply {
+        MAX_SUPPLY.save(deps.storage, &max_supply)?;
+    }
+    MINTED_COUNT.save(deps.storage, &0)?;
+    MINT_RUN.save(deps.storage, &1)?;
+    if let Some(token_id_template) = &msg.token_id_template {
+        validate_token_id_template(token_id_template)?;
+        TOKEN_ID_TEMPLATE.save(deps.storage, token_id_template)?;
+    }
+    if let Some(max_mints_per_address) = msg.max_mints_per_address {
+        MAX_MINTS_PER_ADDRESS.save(deps.storage, &max_mints_per_address)?;
+    }
+    save_sale_window(deps.storage, msg.sale_start, msg.sale_end)?;
+    ViewingKey::set_seed(deps.storage, msg.entropy.as_bytes());
+    PRNG_SEED.save(deps.storage, &sha_256(msg.entropy.as_bytes()).to_vec())?;
+    RANDOM_METADATA_POOL.save(deps.storage, &msg.random_metadata_pool.unwrap_or(false))?;
+    save_contract_version(deps.storage, PKG_NAME, PKG_VERSION)?;
+    CURRENT_CODE_HASH.save(deps.storage, &env.contract.code_hash)?;
     PURCHASABLE_METADATA.save(
         deps.storage,
         &PurchasableMetadata {
@@ -50,53 +128,234 @@ pub fn instantiate(
             private_metadata: msg.private_metadata,
         },
     )?;
+    if let Some(existing_snip721) = msg.existing_snip721 {
+        let subscribe_wasm_msg = adopt_child_snip721(deps, &env, &existing_snip721)?;
+        return Ok(Response::new()
+            .add_message(subscribe_wasm_msg)
+            .add_messages(register_receive_msgs));
+    }
+    let (snip721_code_id, snip721_code_hash, snip721_label) =
+        match (msg.snip721_code_id, msg.snip721_code_hash, msg.snip721_label) {
+            (Some(code_id), Some(code_hash), Some(label)) => (code_id, code_hash, label),
+            _ => {
+                return Err(StdError::generic_err(
+                    "snip721_code_id, snip721_code_hash and snip721_label are required unless existing_snip721 is set",
+                ))
+            }
+        };
+    CHILD_SNIP721_CODE_HASH.save(deps.storage, &snip721_code_hash)?;
+    let snip721_config = msg.snip721_config.unwrap_or_default();
     let instantiate_msg = MigratableSnip721InstantiateMsg {
         instantiate: Snip721InstantiateMsg {
-            name: "PurchasableSnip721".to_string(),
-            symbol: "PUR721".to_string(),
+            name: msg
+                .snip721_name
+                .unwrap_or_else(|| "PurchasableSnip721".to_string()),
+            symbol: msg.snip721_symbol.unwrap_or_else(|| "PUR721".to_string()),
             admin: Some(temp_snip721_admin.to_string()),
             entropy: msg.entropy,
             royalty_info: msg.royalty_info,
             config: Some(InstantiateConfig {
-                public_token_supply: Some(true),
-                public_owner: Some(true),
-                enable_sealed_metadata: None,
-                unwrapped_metadata_is_private: None,
-                minter_may_update_metadata: None,
-                owner_may_update_metadata: None,
-                enable_burn: Some(false),
+                public_token_supply: snip721_config.public_token_supply.or(Some(true)),
+                public_owner: snip721_config.public_owner.or(Some(true)),
+                enable_sealed_metadata: snip721_config.enable_sealed_metadata,
+                unwrapped_metadata_is_private: snip721_config.unwrapped_metadata_is_private,
+                minter_may_update_metadata: snip721_config.minter_may_update_metadata,
+                owner_may_update_metadata: snip721_config.owner_may_update_metadata,
+                enable_burn: snip721_config.enable_burn.or(Some(false)),
             }),
             post_init_callback: None,
             post_init_data: None,
         },
         max_migration_complete_event_subscribers: 1,
+        // only this contract subscribes to its child snip721
+        migration_complete_event_subscription_policy: Some(
+            MigrationCompleteEventSubscriptionPolicy::SelfOnly,
+        ),
     };
     let instantiate_wasm_msg = WasmMsg::Instantiate {
         admin: Some(true_admin.to_string()),
-        code_id: msg.snip721_code_id,
-        code_hash: msg.snip721_code_hash,
+        code_id: snip721_code_id,
+        code_hash: snip721_code_hash,
         msg: to_binary(&instantiate_msg).unwrap(),
         funds: vec![],
-        label: msg.snip721_label,
+        label: snip721_label,
     };
 
-    Ok(Response::new().add_submessages([SubMsg::reply_on_success(
-        instantiate_wasm_msg,
-        INSTANTIATE_SNIP721_REPLY_ID,
-    )]))
+    Ok(Response::new()
+        .add_submessages([SubMsg::reply_on_success(
+            instantiate_wasm_msg,
+            INSTANTIATE_SNIP721_REPLY_ID,
+        )])
+        .add_messages(register_receive_msgs))
 }
 
-#[entry_point]
-pub fn execute(
+/// Checks that a token id template contains {serial} and no placeholder other than {serial} and
+/// {mint_run}, which are the only ones filled in when minting
+fn validate_token_id_template(token_id_template: &str) -> StdResult<()> {
+    if !token_id_template.contains("{serial}") {
+        return Err(StdError::generic_err(
+            "The token id template must contain {serial}",
+        ));
+    }
+    let mut rest = token_id_template;
+    while let Some(start) = rest.find('{') {
+        let placeholder = match rest[start..].find('}') {
+            Some(end) => &rest[start..=start + end],
+            None => &rest[start..],
+        };
+        if placeholder != "{serial}" && placeholder != "{mint_run}" {
+            return Err(StdError::generic_err(format!(
+                "Unknown placeholder {} in the token id template",
+                placeholder
+            )));
+        }
+        rest = &rest[start + placeholder.len()..];
+    }
+    Ok(())
+}
+
+/// Stores `existing_snip721` as the child snip721 and subscribes to its migration complete event.
+/// Purchases stay disabled until ConfirmMinter finds this contract in its minters
+fn adopt_child_snip721(
     deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    msg: ExecuteMsg,
-) -> StdResult<Response> {
+    env: &Env,
+    existing_snip721: &ContractInfo,
+) -> StdResult<WasmMsg> {
+    let raw_existing_snip721 = canonicalize(deps.api, existing_snip721)?;
+    let existing_snip721_address = deps.api.addr_humanize(&raw_existing_snip721.address)?;
+    CHILD_SNIP721_ADDRESS.save(deps.storage, &raw_existing_snip721.address)?;
+    CHILD_SNIP721_CODE_HASH.save(deps.storage, &raw_existing_snip721.code_hash)?;
+    CHILD_SNIP721_MINTER_CONFIRMED.save(deps.storage, &false)?;
+    subscribe_to_child_snip721(
+        deps,
+        env,
+        &raw_existing_snip721.address,
+        &existing_snip721_address,
+        raw_existing_snip721.code_hash,
+    )
+}
+
+/// Adds the child snip721 to this contract's migration complete event subscribers and returns
+/// the message that subscribes this contract to the child's migration complete event
+fn subscribe_to_child_snip721(
+    deps: DepsMut,
+    env: &Env,
+    raw_child_snip721_address: &CanonicalAddr,
+    child_snip721_address: &Addr,
+    child_snip721_code_hash: String,
+) -> StdResult<WasmMsg> {
+    add_migration_complete_event_subscriber(
+        deps.storage,
+        raw_child_snip721_address,
+        &child_snip721_code_hash,
+    )?;
+    Ok(WasmMsg::Execute {
+        contract_addr: child_snip721_address.to_string(),
+        code_hash: child_snip721_code_hash,
+        msg: to_binary(&MigratableExecuteMsg::SubscribeToMigrationCompleteEvent {
+            address: env.contract.address.to_string(),
+            code_hash: env.contract.code_hash.clone(),
+        })?,
+        funds: vec![],
+    })
+}
+
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
     let mut deps = deps;
     match msg {
         ExecuteMsg::Dealer(dealer_msg) => match dealer_msg {
-            DealerExecuteMsg::PurchaseMint { .. } => purchase_and_mint(&mut deps, info),
+            DealerExecuteMsg::PurchaseMint { quantity, voucher } => {
+                purchase_and_mint(&mut deps, env, info, quantity, voucher)
+            }
+            DealerExecuteMsg::RetryFailedMigrationCompleteNotifications {} => {
+                assert_is_admin(deps.as_ref(), &info)?;
+                let msgs = retry_failed_migration_complete_notifications(deps, &env.contract)?;
+                Ok(Response::new().add_submessages(msgs))
+            }
+            DealerExecuteMsg::UnsubscribeFromMigrationCompleteEvent { address } => {
+                let admin = ADMIN.load(deps.storage)?;
+                unsubscribe_from_migration_complete_event(deps, &info, &admin, address)
+            }
+            DealerExecuteMsg::SetPrices { prices } => {
+                assert_is_admin(deps.as_ref(), &info)?;
+                set_prices(deps, prices)
+            }
+            DealerExecuteMsg::ProposeAdmin { address } => {
+                assert_is_admin(deps.as_ref(), &info)?;
+                propose_admin(deps, address)
+            }
+            DealerExecuteMsg::AcceptAdmin {} => accept_admin(deps, info),
+            DealerExecuteMsg::SetPayees { payees } => {
+                assert_is_admin(deps.as_ref(), &info)?;
+                let payees = canonicalize_payees(deps.as_ref(), &payees)?;
+                PAYEES.save(deps.storage, &payees)?;
+                Ok(Response::new())
+            }
+            DealerExecuteMsg::SetPresale { presale } => {
+                assert_is_admin(deps.as_ref(), &info)?;
+                match presale {
+                    Some(presale) => {
+                        validate_prices(&presale.prices, false)?;
+                        assert_prices_are_positive(&presale.prices)?;
+                        PRESALE.save(deps.storage, &presale)?;
+                    }
+                    None => PRESALE.remove(deps.storage),
+                }
+                Ok(Response::new())
+            }
+            DealerExecuteMsg::AddToAllowlist { addresses } => {
+                assert_is_admin(deps.as_ref(), &info)?;
+                set_allowlisted(deps, addresses, true)
+            }
+            DealerExecuteMsg::RemoveFromAllowlist { addresses } => {
+                assert_is_admin(deps.as_ref(), &info)?;
+                set_allowlisted(deps, addresses, false)
+            }
+            DealerExecuteMsg::SetSaleWindow {
+                sale_start,
+                sale_end,
+            } => {
+                assert_is_admin(deps.as_ref(), &info)?;
+                save_sale_window(deps.storage, sale_start, sale_end)?;
+                Ok(Response::new())
+            }
+            DealerExecuteMsg::Withdraw { denom, amount } => withdraw(deps, info, denom, amount),
+            DealerExecuteMsg::CreateViewingKey { entropy } => {
+                let key = ViewingKey::create(
+                    deps.storage,
+                    &info,
+                    &env,
+                    info.sender.as_str(),
+                    entropy.as_bytes(),
+                );
+                Ok(Response::new().set_data(to_binary(&ExecuteAnswer::ViewingKey { key })?))
+            }
+            DealerExecuteMsg::SetViewingKey { key } => {
+                ViewingKey::set(deps.storage, info.sender.as_str(), key.as_str());
+                Ok(Response::new().set_data(to_binary(&ExecuteAnswer::ViewingKey { key })?))
+            }
+            DealerExecuteMsg::StartMintRun { max_supply } => {
+                assert_is_admin(deps.as_ref(), &info)?;
+                start_mint_run(deps, max_supply)
+            }
+            DealerExecuteMsg::AddToMetadataPool { metadata } => {
+                assert_is_admin(deps.as_ref(), &info)?;
+                add_to_metadata_pool(deps, metadata)
+            }
+            DealerExecuteMsg::ConfirmMinter {} => confirm_minter(deps, env),
+            DealerExecuteMsg::RevokePermit { permit_name } => {
+                RevokedPermits::revoke_permit(
+                    deps.storage,
+                    PREFIX_REVOKED_PERMITS,
+                    info.sender.as_str(),
+                    permit_name.as_str(),
+                );
+                Ok(Response::new())
+            }
+            DealerExecuteMsg::Receive {
+                from, amount, msg, ..
+            } => receive_and_mint(&mut deps, env, info, from, amount, msg),
         },
         ExecuteMsg::Migrate(migrate_msg) => match migrate_msg {
             MigratableExecuteMsg::SubscribeToMigrationCompleteEvent { address, code_hash } => {
@@ -112,6 +371,176 @@ pub fn execute(
     }
 }
 
+/// Returns an error if no prices are given while no SNIP-20 token is accepted, or if more than one
+/// price is given for a denom
+fn validate_prices(prices: &[Coin], accepts_snip20: bool) -> StdResult<()> {
+    if prices.is_empty() && !accepts_snip20 {
+        return Err(StdError::generic_err("No purchase prices were specified"));
+    }
+    for (i, price) in prices.iter().enumerate() {
+        if prices[..i].iter().any(|p| p.denom == price.denom) {
+            return Err(StdError::generic_err(format!(
+                "Multiple purchase prices were specified for denom: {}",
+                price.denom
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Returns an error if any price is zero
+fn assert_prices_are_positive(prices: &[Coin]) -> StdResult<()> {
+    match prices.iter().find(|price| price.amount.is_zero()) {
+        Some(price) => Err(StdError::generic_err(format!(
+            "Purchase price for denom {} must be greater than 0",
+            price.denom
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Converts SNIP-20 prices to their stored form. Returns an error if a price is zero or if more
+/// than one price is given for a token
+fn canonicalize_snip20_prices(
+    deps: Deps,
+    snip20_prices: Option<Vec<Snip20Price>>,
+) -> StdResult<Vec<StoredSnip20Price>> {
+    let mut stored_snip20_prices: Vec<StoredSnip20Price> = vec![];
+    for snip20_price in snip20_prices.unwrap_or_default() {
+        if snip20_price.amount.is_zero() {
+            return Err(StdError::generic_err(format!(
+                "Purchase price for token {} must be greater than 0",
+                snip20_price.token.address
+            )));
+        }
+        let token = canonicalize(deps.api, &snip20_price.token)?;
+        if stored_snip20_prices
+            .iter()
+            .any(|p| p.token.address == token.address)
+        {
+            return Err(StdError::generic_err(format!(
+                "Multiple purchase prices were specified for token: {}",
+                snip20_price.token.address
+            )));
+        }
+        stored_snip20_prices.push(StoredSnip20Price {
+            token,
+            amount: snip20_price.amount,
+        });
+    }
+    Ok(stored_snip20_prices)
+}
+
+fn set_prices(deps: DepsMut, prices: Vec<Coin>) -> StdResult<Response> {
+    let accepts_snip20 = !SNIP20_PRICES
+        .load(deps.storage)
+        .unwrap_or_default()
+        .is_empty();
+    validate_prices(&prices, accepts_snip20)?;
+    assert_prices_are_positive(&prices)?;
+    let old_prices = PURCHASE_PRICES.load(deps.storage)?;
+    PURCHASE_PRICES.save(deps.storage, &prices)?;
+    Ok(Response::new()
+        .add_attribute("old_prices", format_prices(&old_prices))
+        .add_attribute("new_prices", format_prices(&prices)))
+}
+
+/// Formats prices as a comma separated list of amount and denom, e.g. `100uscrt,5uatom`
+fn format_prices(prices: &[Coin]) -> String {
+    prices
+        .iter()
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn propose_admin(deps: DepsMut, address: String) -> StdResult<Response> {
+    let proposed_admin = deps.api.addr_validate(address.as_str())?;
+    PENDING_ADMIN.save(
+        deps.storage,
+        &deps.api.addr_canonicalize(proposed_admin.as_str())?,
+    )?;
+    Ok(Response::new().add_attribute("pending_admin", proposed_admin))
+}
+
+/// Makes the pending admin the admin of this contract and sends ChangeAdmin to the child snip721
+/// so both contracts stay controlled by the same address. The ChangeAdmin only succeeds while this
+/// contract is the child snip721's admin, so the whole handoff reverts if it is not
+fn accept_admin(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let raw_sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if PENDING_ADMIN.may_load(deps.storage)? != Some(raw_sender.clone()) {
+        return Err(StdError::generic_err(
+            "Only the pending admin can accept the admin role",
+        ));
+    }
+    ADMIN.save(deps.storage, &raw_sender)?;
+    PENDING_ADMIN.remove(deps.storage);
+    let child_snip721_address = CHILD_SNIP721_ADDRESS.load(deps.storage)?;
+    let change_admin_wasm_msg = WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(&child_snip721_address)?.to_string(),
+        code_hash: CHILD_SNIP721_CODE_HASH.load(deps.storage)?,
+        msg: to_binary(&ChangeAdmin {
+            address: info.sender.to_string(),
+            padding: None,
+        })?,
+        funds: vec![],
+    };
+    Ok(Response::new()
+        .add_message(change_admin_wasm_msg)
+        .add_attribute("admin", info.sender))
+}
+
+/// Enables purchases once the adopted child snip721 lists this contract as a minter. Anyone may
+/// call it
+fn confirm_minter(deps: DepsMut, env: Env) -> StdResult<Response> {
+    let child_snip721_address = deps
+        .api
+        .addr_humanize(&CHILD_SNIP721_ADDRESS.load(deps.storage)?)?;
+    let minters = match deps.querier.query_wasm_smart(
+        CHILD_SNIP721_CODE_HASH.load(deps.storage)?,
+        child_snip721_address.to_string(),
+        &Snip721QueryMsg::Minters {},
+    )? {
+        Snip721QueryAnswer::MinterList { minters } => minters,
+        _ => {
+            return Err(StdError::generic_err(
+                "Unexpected response to the child snip721 Minters query",
+            ))
+        }
+    };
+    if !minters.contains(&env.contract.address) {
+        return Err(StdError::generic_err(format!(
+            "{} is not a minter of the child snip721 {}",
+            env.contract.address, child_snip721_address
+        )));
+    }
+    CHILD_SNIP721_MINTER_CONFIRMED.save(deps.storage, &true)?;
+    Ok(Response::new())
+}
+
+/// Returns an error if this contract has not been confirmed as a minter of its child snip721
+fn assert_minter_is_confirmed(storage: &dyn Storage) -> StdResult<()> {
+    if !CHILD_SNIP721_MINTER_CONFIRMED
+        .may_load(storage)?
+        .unwrap_or(true)
+    {
+        return Err(StdError::generic_err(
+            "Purchases are disabled until ConfirmMinter confirms this contract is a minter of the child snip721",
+        ));
+    }
+    Ok(())
+}
+
+/// Returns an error if the message was not sent by this contract's admin
+fn assert_is_admin(deps: Deps, info: &MessageInfo) -> StdResult<()> {
+    if deps.api.addr_canonicalize(info.sender.as_str())? != ADMIN.load(deps.storage)? {
+        return Err(StdError::generic_err(
+            "This is an admin command and can only be run from the admin address",
+        ));
+    }
+    Ok(())
+}
+
 fn update_child_snip721(
     deps: DepsMut,
     info: MessageInfo,
@@ -132,7 +561,27 @@ fn update_child_snip721(
     Ok(Response::new())
 }
 
-fn purchase_and_mint(deps: &mut DepsMut, info: MessageInfo) -> StdResult<Response> {
+fn purchase_and_mint(
+    deps: &mut DepsMut,
+    env: Env,
+    info: MessageInfo,
+    quantity: Option<u32>,
+    voucher: Option<Voucher>,
+) -> StdResult<Response> {
+    let quantity = validate_quantity(quantity)?;
+    assert_minter_is_confirmed(deps.storage)?;
+    let presale = match assert_sale_is_open(deps.storage, &env.block)? {
+        SaleStatus::Presale => {
+            let presale = PRESALE.load(deps.storage)?;
+            assert_is_allowlisted(deps.as_ref(), &env, &info.sender, voucher, &presale)?;
+            Some(presale)
+        }
+        _ => None,
+    };
+    let prices = match &presale {
+        Some(presale) => presale.prices.clone(),
+        None => PURCHASE_PRICES.load(deps.storage)?,
+    };
     if info.funds.len() != 1 {
         return Err(StdError::generic_err(format!(
             "Purchase requires one coin denom to be sent with transaction, {} were sent.",
@@ -140,13 +589,15 @@ fn purchase_and_mint(deps: &mut DepsMut, info: MessageInfo) -> StdResult<Respons
         )));
     }
     let msg_fund = &info.funds[0];
-    let prices = PURCHASE_PRICES.load(deps.storage)?;
     let selected_coin_price = prices.iter().find(|c| c.denom == msg_fund.denom);
     if let Some(selected_coin_price) = selected_coin_price {
-        if msg_fund.amount != selected_coin_price.amount {
+        let total_price = selected_coin_price
+            .amount
+            .checked_mul(Uint128::from(quantity))?;
+        if msg_fund.amount != total_price {
             return Err(StdError::generic_err(format!(
                 "Purchase price in {} is {}, but {} was sent",
-                selected_coin_price.denom, selected_coin_price.amount, msg_fund
+                selected_coin_price.denom, total_price, msg_fund
             )));
         }
     } else {
@@ -155,86 +606,456 @@ fn purchase_and_mint(deps: &mut DepsMut, info: MessageInfo) -> StdResult<Respons
             msg_fund.denom
         )));
     }
-    let sender = info.clone().sender;
-    let admin_addr = &deps.api.addr_humanize(&ADMIN.load(deps.storage)?)?;
-    let send_funds_bank_msg = CosmosMsg::Bank(BankMsg::Send {
-        to_address: admin_addr.to_string(),
-        amount: info.funds.clone(),
-    });
-    let purchasable_metadata: PurchasableMetadata = PURCHASABLE_METADATA.load(deps.storage)?;
-    let mint_nft_msg = MintNft {
-        token_id: None,
-        owner: Some(sender.to_string()),
-        public_metadata: purchasable_metadata.public_metadata,
-        private_metadata: purchasable_metadata.private_metadata,
-        serial_number: None,
-        royalty_info: None,
-        transferable: None,
-        memo: None,
-        padding: None,
+    let raw_sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let first_serial_number = record_mints(deps.storage, &raw_sender, presale.as_ref(), quantity)?;
+    let send_funds_msgs = pay_payees(deps.branch(), msg_fund)?;
+    let mint_wasm_msg = mint_msg(
+        deps.branch(),
+        &env,
+        info.sender,
+        first_serial_number,
+        quantity,
+    )?;
+
+    Ok(Response::new()
+        .add_messages(send_funds_msgs)
+        .add_message(mint_wasm_msg))
+}
+
+/// Purchases mints for `from` when their price in a SNIP-20 token is sent to this contract.
+/// info.sender is the token contract
+fn receive_and_mint(
+    deps: &mut DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from: String,
+    amount: Uint128,
+    msg: Option<Binary>,
+) -> StdResult<Response> {
+    assert_minter_is_confirmed(deps.storage)?;
+    if assert_sale_is_open(deps.storage, &env.block)? == SaleStatus::Presale {
+        return Err(StdError::generic_err(
+            "SNIP-20 purchases are only accepted once the public sale opens",
+        ));
+    }
+    let quantity = match msg {
+        Some(msg) => match from_binary(&msg)? {
+            ReceiveMsg::PurchaseMint { quantity } => quantity,
+        },
+        None => None,
+    };
+    let quantity = validate_quantity(quantity)?;
+    let raw_token = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let snip20_prices = SNIP20_PRICES.load(deps.storage).unwrap_or_default();
+    let snip20_price = snip20_prices
+        .iter()
+        .find(|p| p.token.address == raw_token)
+        .ok_or_else(|| {
+            StdError::generic_err(format!(
+                "Purchasing with token:{} is not allowed",
+                info.sender
+            ))
+        })?;
+    let total_price = snip20_price.amount.checked_mul(Uint128::from(quantity))?;
+    if amount != total_price {
+        return Err(StdError::generic_err(format!(
+            "Purchase price in token {} is {}, but {} was sent",
+            info.sender, total_price, amount
+        )));
+    }
+    let owner = deps.api.addr_validate(from.as_str())?;
+    let first_serial_number = record_mints(
+        deps.storage,
+        &deps.api.addr_canonicalize(owner.as_str())?,
+        None,
+        quantity,
+    )?;
+    let payment = Coin {
+        denom: info.sender.to_string(),
+        amount,
+    };
+    let send_funds_msgs = pay_payees(deps.branch(), &payment)?;
+    let mint_wasm_msg = mint_msg(deps.branch(), &env, owner, first_serial_number, quantity)?;
+
+    Ok(Response::new()
+        .add_messages(send_funds_msgs)
+        .add_message(mint_wasm_msg))
+}
+
+/// Saves the times the sale starts and ends, removing either if missing. Returns an error if both
+/// are given in the same unit and the sale would not end after it starts
+fn save_sale_window(
+    storage: &mut dyn Storage,
+    sale_start: Option<SaleTime>,
+    sale_end: Option<SaleTime>,
+) -> StdResult<()> {
+    let ends_before_start = match (&sale_start, &sale_end) {
+        (Some(SaleTime::AtHeight(start)), Some(SaleTime::AtHeight(end))) => end <= start,
+        (Some(SaleTime::AtTime(start)), Some(SaleTime::AtTime(end))) => end <= start,
+        _ => false,
+    };
+    if ends_before_start {
+        return Err(StdError::generic_err("The sale must end after it starts"));
+    }
+    match sale_start {
+        Some(sale_start) => SALE_START.save(storage, &sale_start)?,
+        None => SALE_START.remove(storage),
+    }
+    match sale_end {
+        Some(sale_end) => SALE_END.save(storage, &sale_end)?,
+        None => SALE_END.remove(storage),
+    }
+    Ok(())
+}
+
+/// Returns true if `block` is at or after `sale_time`
+fn is_reached(sale_time: &SaleTime, block: &BlockInfo) -> bool {
+    match sale_time {
+        SaleTime::AtHeight(height) => block.height >= *height,
+        SaleTime::AtTime(time) => block.time >= *time,
+    }
+}
+
+fn sale_status(storage: &dyn Storage, block: &BlockInfo) -> StdResult<SaleStatus> {
+    if let Some(sale_end) = SALE_END.may_load(storage)? {
+        if is_reached(&sale_end, block) {
+            return Ok(SaleStatus::Ended);
+        }
+    }
+    if let Some(sale_start) = SALE_START.may_load(storage)? {
+        if !is_reached(&sale_start, block) {
+            return Ok(SaleStatus::Upcoming);
+        }
+    }
+    if let Some(presale) = PRESALE.may_load(storage)? {
+        if !is_reached(&presale.public_sale_start, block) {
+            return Ok(SaleStatus::Presale);
+        }
+    }
+    Ok(SaleStatus::Active)
+}
+
+/// Returns an error if the sale has not started yet or has ended, otherwise returns whether it is
+/// in its presale or active
+fn assert_sale_is_open(storage: &dyn Storage, block: &BlockInfo) -> StdResult<SaleStatus> {
+    match sale_status(storage, block)? {
+        SaleStatus::Upcoming => Err(StdError::generic_err("The sale has not started yet")),
+        SaleStatus::Ended => Err(StdError::generic_err("The sale has ended")),
+        status => Ok(status),
+    }
+}
+
+fn set_allowlisted(
+    deps: DepsMut,
+    addresses: Vec<String>,
+    allowlisted: bool,
+) -> StdResult<Response> {
+    for address in addresses {
+        let raw_address = deps
+            .api
+            .addr_canonicalize(deps.api.addr_validate(address.as_str())?.as_str())?;
+        let allowlist = ALLOWLIST.add_suffix(raw_address.as_slice());
+        if allowlisted {
+            allowlist.save(deps.storage, &true)?;
+        } else {
+            allowlist.remove(deps.storage);
+        }
+    }
+    Ok(Response::new())
+}
+
+/// Returns an error unless `buyer` is on the presale allowlist or `voucher` was signed for `buyer`
+/// by the presale's voucher key
+fn assert_is_allowlisted(
+    deps: Deps,
+    env: &Env,
+    buyer: &Addr,
+    voucher: Option<Voucher>,
+    presale: &Presale,
+) -> StdResult<()> {
+    let raw_buyer = deps.api.addr_canonicalize(buyer.as_str())?;
+    if ALLOWLIST
+        .add_suffix(raw_buyer.as_slice())
+        .may_load(deps.storage)?
+        .unwrap_or(false)
+    {
+        return Ok(());
+    }
+    if let (Some(voucher), Some(voucher_public_key)) = (voucher, &presale.voucher_public_key) {
+        let voucher_hash = sha_256(format!("{}:{}", env.contract.address, buyer).as_bytes());
+        if let Ok(true) = deps.api.secp256k1_verify(
+            &voucher_hash,
+            voucher.signature.as_slice(),
+            voucher_public_key.as_slice(),
+        ) {
+            return Ok(());
+        }
+    }
+    Err(StdError::generic_err(format!(
+        "{} is not allowed to purchase during the presale",
+        buyer
+    )))
+}
+
+/// Returns the number of mints to purchase, 1 if missing. Returns an error if it is 0
+fn validate_quantity(quantity: Option<u32>) -> StdResult<u32> {
+    match quantity.unwrap_or(1) {
+        0 => Err(StdError::generic_err("Quantity must be greater than 0")),
+        quantity => Ok(quantity),
+    }
+}
+
+/// Adds `quantity` to the number of mints purchased overall and by `buyer`. Returns an error if
+/// that would exceed the max supply or the max mints per address. Mints purchased during the
+/// presale are counted against the presale's max mints per address
+fn record_mints(
+    storage: &mut dyn Storage,
+    buyer: &CanonicalAddr,
+    presale: Option<&Presale>,
+    quantity: u32,
+) -> StdResult<u32> {
+    let minted = MINTED_COUNT.may_load(storage)?.unwrap_or_default();
+    if let Some(max_supply) = MAX_SUPPLY.may_load(storage)? {
+        if minted >= max_supply {
+            return Err(StdError::generic_err(format!(
+                "Sold out: all {} mints have been purchased",
+                max_supply
+            )));
+        }
+        if quantity > max_supply - minted {
+            return Err(StdError::generic_err(format!(
+                "Only {} mints remain, but {} were requested",
+                max_supply - minted,
+                quantity
+            )));
+        }
+    }
+    if RANDOM_METADATA_POOL.may_load(storage)?.unwrap_or(false) {
+        let pool_size = METADATA_POOL.get_len(storage)?;
+        if pool_size == 0 {
+            return Err(StdError::generic_err(
+                "Sold out: the metadata pool is exhausted",
+            ));
+        }
+        if quantity > pool_size {
+            return Err(StdError::generic_err(format!(
+                "Only {} mints remain, but {} were requested",
+                pool_size, quantity
+            )));
+        }
+    }
+    let (buyer_purchase_counts, max_mints_per_address, phase) = match presale {
+        Some(presale) => (
+            PRESALE_PURCHASE_COUNTS.add_suffix(buyer.as_slice()),
+            presale.max_mints_per_address,
+            " during the presale",
+        ),
+        None => (
+            PURCHASE_COUNTS.add_suffix(buyer.as_slice()),
+            MAX_MINTS_PER_ADDRESS.may_load(storage)?,
+            "",
+        ),
+    };
+    let purchased = buyer_purchase_counts.may_load(storage)?.unwrap_or_default();
+    let purchased = checked_add_count(purchased, quantity)?;
+    if let Some(max_mints_per_address) = max_mints_per_address {
+        if purchased > max_mints_per_address {
+            return Err(StdError::generic_err(format!(
+                "Only {} mints can be purchased per address{}",
+                max_mints_per_address, phase
+            )));
+        }
+    }
+    buyer_purchase_counts.save(storage, &purchased)?;
+    MINTED_COUNT.save(storage, &checked_add_count(minted, quantity)?)?;
+    checked_add_count(minted, 1)
+}
+
+fn checked_add_count(count: u32, quantity: u32) -> StdResult<u32> {
+    count.checked_add(quantity).ok_or_else(|| {
+        StdError::overflow(OverflowError::new(OverflowOperation::Add, count, quantity))
+    })
+}
+
+/// Returns the message that mints `quantity` tokens to `owner` on the child snip721, with the
+/// purchasable metadata or metadata drawn from the random metadata pool. Serial numbers in the
+/// current mint run start at `first_serial_number`. More than one token is minted with a single
+/// BatchMintNft
+fn mint_msg(
+    deps: DepsMut,
+    env: &Env,
+    owner: Addr,
+    first_serial_number: u32,
+    quantity: u32,
+) -> StdResult<CosmosMsg> {
+    let metadata = if RANDOM_METADATA_POOL
+        .may_load(deps.storage)?
+        .unwrap_or(false)
+    {
+        draw_pool_metadata(deps.storage, env, quantity)?
+    } else {
+        vec![PURCHASABLE_METADATA.load(deps.storage)?; quantity as usize]
+    };
+    let mint_run = MINT_RUN.may_load(deps.storage)?.unwrap_or(1);
+    let max_supply = MAX_SUPPLY.may_load(deps.storage)?;
+    let token_id_template = TOKEN_ID_TEMPLATE.may_load(deps.storage)?;
+    let mut mints = metadata
+        .into_iter()
+        .zip(first_serial_number..)
+        .map(|(purchasable_metadata, serial_number)| Mint {
+            token_id: token_id_template.as_ref().map(|template| {
+                template
+                    .replace("{mint_run}", &mint_run.to_string())
+                    .replace("{serial}", &serial_number.to_string())
+            }),
+            owner: Some(owner.to_string()),
+            public_metadata: purchasable_metadata.public_metadata,
+            private_metadata: purchasable_metadata.private_metadata,
+            serial_number: Some(SerialNumber {
+                mint_run: Some(mint_run),
+                serial_number,
+                quantity_minted_this_run: max_supply,
+            }),
+            royalty_info: None,
+            transferable: None,
+            memo: None,
+        })
+        .collect::<Vec<_>>();
+    let mint_nft_msg = if quantity == 1 {
+        let mint = mints.remove(0);
+        MintNft {
+            token_id: mint.token_id,
+            owner: mint.owner,
+            public_metadata: mint.public_metadata,
+            private_metadata: mint.private_metadata,
+            serial_number: mint.serial_number,
+            royalty_info: None,
+            transferable: None,
+            memo: None,
+            padding: None,
+        }
+    } else {
+        BatchMintNft {
+            mints,
+            padding: None,
+        }
     };
     let child_snip721_code_hash = CHILD_SNIP721_CODE_HASH.load(deps.storage)?;
     let child_snip721_address = CHILD_SNIP721_ADDRESS.load(deps.storage)?;
-    let mint_wasm_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: deps.api.addr_humanize(&child_snip721_address)?.to_string(),
         code_hash: child_snip721_code_hash,
         msg: to_binary(&mint_nft_msg)?,
         funds: vec![],
-    });
+    }))
+}
 
-    Ok(Response::new()
-        .add_submessages([SubMsg::new(send_funds_bank_msg), SubMsg::new(mint_wasm_msg)]))
+/// Starts the next mint run with `max_supply` mints for sale. The token id template must include
+/// the mint run so token ids of different runs cannot collide
+fn start_mint_run(deps: DepsMut, max_supply: Option<u32>) -> StdResult<Response> {
+    if let Some(token_id_template) = TOKEN_ID_TEMPLATE.may_load(deps.storage)? {
+        if !token_id_template.contains("{mint_run}") {
+            return Err(StdError::generic_err(
+                "The token id template must contain {mint_run} to start another mint run",
+            ));
+        }
+    }
+    let mint_run = checked_add_count(MINT_RUN.may_load(deps.storage)?.unwrap_or(1), 1)?;
+    MINT_RUN.save(deps.storage, &mint_run)?;
+    MINTED_COUNT.save(deps.storage, &0)?;
+    match max_supply {
+        Some(max_supply) => MAX_SUPPLY.save(deps.storage, &max_supply)?,
+        None => MAX_SUPPLY.remove(deps.storage),
+    }
+    Ok(Response::new().add_attribute("mint_run", mint_run.to_string()))
+}
+
+/// Removes `quantity` metadata drawn at random from the metadata pool. Each draw hashes the
+/// stored seed mixed with the block's random bytes, and the last hash is stored as the next seed
+fn draw_pool_metadata(
+    storage: &mut dyn Storage,
+    env: &Env,
+    quantity: u32,
+) -> StdResult<Vec<PurchasableMetadata>> {
+    let mut seed = PRNG_SEED.load(storage)?;
+    if let Some(random) = &env.block.random {
+        seed.extend_from_slice(random.as_slice());
+    }
+    let mut drawn = Vec::with_capacity(quantity as usize);
+    for _ in 0..quantity {
+        seed = sha_256(&seed).to_vec();
+        let mut index_bytes = [0u8; 8];
+        index_bytes.copy_from_slice(&seed[..8]);
+        let pool_size = METADATA_POOL.get_len(storage)?;
+        let index = (u64::from_be_bytes(index_bytes) % u64::from(pool_size)) as u32;
+        // move the last metadata into the drawn slot so the pool stays contiguous
+        let last = METADATA_POOL.pop(storage)?;
+        if index == pool_size - 1 {
+            drawn.push(last);
+        } else {
+            drawn.push(METADATA_POOL.get_at(storage, index)?);
+            METADATA_POOL.set_at(storage, index, &last)?;
+        }
+    }
+    PRNG_SEED.save(storage, &seed)?;
+    Ok(drawn)
+}
+
+/// Appends `metadata` to the random metadata pool
+fn add_to_metadata_pool(deps: DepsMut, metadata: Vec<PurchasableMetadata>) -> StdResult<Response> {
+    if !RANDOM_METADATA_POOL
+        .may_load(deps.storage)?
+        .unwrap_or(false)
+    {
+        return Err(StdError::generic_err(
+            "This contract was not instantiated with a random metadata pool",
+        ));
+    }
+    if metadata.is_empty() {
+        return Err(StdError::generic_err("No metadata was specified"));
+    }
+    for purchasable_metadata in &metadata {
+        METADATA_POOL.push(deps.storage, purchasable_metadata)?;
+    }
+    Ok(Response::new().add_attribute(
+        "metadata_pool_size",
+        METADATA_POOL.get_len(deps.storage)?.to_string(),
+    ))
 }
 
 #[entry_point]
 pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
     match msg.id {
         INSTANTIATE_SNIP721_REPLY_ID => on_instantiated_snip721_reply(deps, env, msg),
+        id if id >= MIGRATION_COMPLETE_NOTIFICATION_REPLY_ID_OFFSET => {
+            on_migration_complete_notification_reply(deps, msg)
+        }
         id => Err(StdError::generic_err(format!("Unknown reply id: {}", id))),
     }
 }
 
 fn on_instantiated_snip721_reply(deps: DepsMut, env: Env, reply: Reply) -> StdResult<Response> {
-    let result = reply.result.unwrap();
-    let contract_address = &result
-        .events
-        .iter()
-        .find(|e| e.ty == "instantiate")
-        .unwrap()
-        .attributes
-        .iter()
-        .find(|a| a.key == "contract_address")
-        .unwrap()
-        .value;
+    let contract_address = instantiated_contract_address(reply)?;
     let child_snip721_address = deps.api.addr_validate(contract_address.as_str())?;
     let raw_child_snip721_address = deps.api.addr_canonicalize(child_snip721_address.as_str())?;
     CHILD_SNIP721_ADDRESS.save(deps.storage, &raw_child_snip721_address)?;
     let child_snip721_code_hash: String = CHILD_SNIP721_CODE_HASH.load(deps.storage)?;
     let admin: Addr = deps.api.addr_humanize(&ADMIN.load(deps.storage)?)?;
-    add_migration_complete_event_subscriber(
-        deps.storage,
+    let subscribe_to_migration_complete_event_wasm_msg = subscribe_to_child_snip721(
+        deps,
+        &env,
         &raw_child_snip721_address,
-        &child_snip721_code_hash,
+        &child_snip721_address,
+        child_snip721_code_hash.clone(),
     )?;
 
-    let subscribe_to_migration_complete_event_wasm_msg = WasmMsg::Execute {
-        contract_addr: child_snip721_address.to_string(),
-        code_hash: child_snip721_code_hash.clone(),
-        msg: to_binary(&MigratableExecuteMsg::SubscribeToMigrationCompleteEvent {
-            address: env.contract.address.to_string(),
-            code_hash: env.contract.code_hash,
-        })?,
-        funds: vec![],
-    };
-
     let change_admin_to_true_admin_wasm_msg = WasmMsg::Execute {
         contract_addr: child_snip721_address.to_string(),
         code_hash: child_snip721_code_hash,
         msg: to_binary(&ChangeAdmin {
             address: admin.to_string(),
             padding: None,
-        })
-        .unwrap(),
+        })?,
         funds: vec![],
     };
 
@@ -244,12 +1065,211 @@ fn on_instantiated_snip721_reply(deps: DepsMut, env: Env, reply: Reply) -> StdRe
     ]))
 }
 
+/// Returns the address of the contract instantiated by the submessage `reply` answers. It is read
+/// from the contract_address attribute of the instantiate event, or from the
+/// MsgInstantiateContractResponse in the reply data when there is no instantiate event
+fn instantiated_contract_address(reply: Reply) -> StdResult<String> {
+    let response = reply.result.into_result().map_err(|err| {
+        StdError::generic_err(format!("Failed to instantiate the child snip721: {}", err))
+    })?;
+    if let Some(event) = response.events.iter().find(|e| e.ty == "instantiate") {
+        return event
+            .attributes
+            .iter()
+            .find(|a| a.key == "contract_address")
+            .map(|a| a.value.clone())
+            .ok_or_else(|| {
+                StdError::generic_err(
+                    "The child snip721 instantiate event has no contract_address attribute",
+                )
+            });
+    }
+    match response.data {
+        Some(data) => parse_instantiate_response_contract_address(data.as_slice()),
+        None => Err(StdError::generic_err(
+            "The child snip721 instantiate reply has neither an instantiate event nor data",
+        )),
+    }
+}
+
+/// Returns the contract_address field (1) of a protobuf encoded MsgInstantiateContractResponse
+fn parse_instantiate_response_contract_address(data: &[u8]) -> StdResult<String> {
+    let mut remaining = data;
+    while !remaining.is_empty() {
+        let key = read_protobuf_varint(&mut remaining)?;
+        match key & 0x7 {
+            // varint
+            0 => {
+                read_protobuf_varint(&mut remaining)?;
+            }
+            // length delimited
+            2 => {
+                let len = read_protobuf_varint(&mut remaining)? as usize;
+                if len > remaining.len() {
+                    return Err(malformed_instantiate_response_err());
+                }
+                let (value, rest) = remaining.split_at(len);
+                if key >> 3 == 1 {
+                    return String::from_utf8(value.to_vec())
+                        .map_err(|_| malformed_instantiate_response_err());
+                }
+                remaining = rest;
+            }
+            _ => return Err(malformed_instantiate_response_err()),
+        }
+    }
+    Err(StdError::generic_err(
+        "The child snip721 MsgInstantiateContractResponse has no contract address",
+    ))
+}
+
+/// Reads a protobuf varint from the start of `data` and advances `data` past it
+fn read_protobuf_varint(data: &mut &[u8]) -> StdResult<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = data
+            .split_first()
+            .ok_or_else(malformed_instantiate_response_err)?;
+        *data = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(malformed_instantiate_response_err())
+}
+
+fn malformed_instantiate_response_err() -> StdError {
+    StdError::generic_err("The child snip721 MsgInstantiateContractResponse is malformed")
+}
+
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetPrices {} => query_prices(deps),
+        QueryMsg::GetSnip20Prices {} => query_snip20_prices(deps),
         QueryMsg::GetChildSnip721 {} => query_child_snip721(deps),
+        QueryMsg::MintCounts {} => query_mint_counts(deps),
+        QueryMsg::Presale {} => to_binary(&QueryAnswer::Presale {
+            presale: PRESALE.may_load(deps.storage)?,
+        }),
+        QueryMsg::SaleStatus {} => to_binary(&QueryAnswer::SaleStatus {
+            status: sale_status(deps.storage, &env.block)?,
+            sale_start: SALE_START.may_load(deps.storage)?,
+            sale_end: SALE_END.may_load(deps.storage)?,
+        }),
+        QueryMsg::PurchaseCount {
+            address,
+            viewing_key,
+        } => {
+            let address = deps.api.addr_validate(address.as_str())?;
+            ViewingKey::check(deps.storage, address.as_str(), viewing_key.as_str())?;
+            query_purchase_count(deps, &address)
+        }
+        QueryMsg::MetadataPoolSize {
+            address,
+            viewing_key,
+        } => {
+            let address = deps.api.addr_validate(address.as_str())?;
+            ViewingKey::check(deps.storage, address.as_str(), viewing_key.as_str())?;
+            query_metadata_pool_size(deps, &address)
+        }
+        QueryMsg::WithPermit { permit, query } => {
+            let address = validate(
+                deps,
+                PREFIX_REVOKED_PERMITS,
+                &permit,
+                env.contract.address.to_string(),
+                None,
+            )?;
+            if !permit.check_permission(&TokenPermissions::Owner) {
+                return Err(StdError::generic_err(
+                    "Permit does not grant the owner permission",
+                ));
+            }
+            let address = deps.api.addr_validate(address.as_str())?;
+            match query {
+                QueryWithPermit::PurchaseCount {} => query_purchase_count(deps, &address),
+                QueryWithPermit::MetadataPoolSize {} => query_metadata_pool_size(deps, &address),
+                QueryWithPermit::Balances {} => query_balances(deps, &address),
+            }
+        }
+        QueryMsg::GetPayees {} => to_binary(&QueryAnswer::GetPayees {
+            payees: load_payees(deps)?,
+        }),
+        QueryMsg::Balances {
+            address,
+            viewing_key,
+        } => {
+            let address = deps.api.addr_validate(address.as_str())?;
+            ViewingKey::check(deps.storage, address.as_str(), viewing_key.as_str())?;
+            query_balances(deps, &address)
+        }
+        QueryMsg::ContractVersion {} => query_contract_version(deps),
+        QueryMsg::FailedMigrationCompleteNotifications {} => {
+            query_failed_migration_complete_notifications(deps)
+        }
+        QueryMsg::MigrationHistory { page, page_size } => {
+            query_migration_history(deps, page, page_size)
+        }
+    }
+}
+
+fn query_failed_migration_complete_notifications(deps: Deps) -> StdResult<Binary> {
+    let failures = load_failed_migration_complete_notifications(deps)?;
+    to_binary(&QueryAnswer::FailedMigrationCompleteNotifications { failures })
+}
+
+fn query_contract_version(deps: Deps) -> StdResult<Binary> {
+    to_binary(&QueryAnswer::ContractVersion(
+        CONTRACT_VERSION.load(deps.storage)?,
+    ))
+}
+
+fn query_migration_history(deps: Deps, page: u32, page_size: u32) -> StdResult<Binary> {
+    to_binary(&QueryAnswer::MigrationHistory {
+        records: MIGRATION_HISTORY.paging(deps.storage, page, page_size)?,
+        count: MIGRATION_HISTORY.get_len(deps.storage)?,
+    })
+}
+
+fn query_mint_counts(deps: Deps) -> StdResult<Binary> {
+    let minted = MINTED_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    let max_supply = MAX_SUPPLY.may_load(deps.storage)?;
+    to_binary(&QueryAnswer::MintCounts {
+        mint_run: MINT_RUN.may_load(deps.storage)?.unwrap_or(1),
+        minted,
+        max_supply,
+        remaining: max_supply.map(|max_supply| max_supply.saturating_sub(minted)),
+    })
+}
+
+fn query_balances(deps: Deps, address: &Addr) -> StdResult<Binary> {
+    to_binary(&QueryAnswer::Balances {
+        balances: load_balances(deps, address)?,
+    })
+}
+
+fn query_purchase_count(deps: Deps, address: &Addr) -> StdResult<Binary> {
+    let raw_address = deps.api.addr_canonicalize(address.as_str())?;
+    to_binary(&QueryAnswer::PurchaseCount {
+        count: PURCHASE_COUNTS
+            .add_suffix(raw_address.as_slice())
+            .may_load(deps.storage)?
+            .unwrap_or_default(),
+        max_mints_per_address: MAX_MINTS_PER_ADDRESS.may_load(deps.storage)?,
+    })
+}
+
+fn query_metadata_pool_size(deps: Deps, address: &Addr) -> StdResult<Binary> {
+    if deps.api.addr_canonicalize(address.as_str())? != ADMIN.load(deps.storage)? {
+        return Err(StdError::generic_err(
+            "Only the admin can query the metadata pool size",
+        ));
     }
+    to_binary(&QueryAnswer::MetadataPoolSize {
+        size: METADATA_POOL.get_len(deps.storage)?,
+    })
 }
 
 fn query_child_snip721(deps: Deps) -> StdResult<Binary> {
@@ -271,3 +1291,18 @@ pub fn query_prices(deps: Deps) -> StdResult<Binary> {
         prices: PURCHASE_PRICES.load(deps.storage)?,
     })
 }
+
+fn query_snip20_prices(deps: Deps) -> StdResult<Binary> {
+    let prices = SNIP20_PRICES
+        .load(deps.storage)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|p| {
+            Ok(Snip20Price {
+                token: p.token.into_humanized(deps.api)?,
+                amount: p.amount,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    to_binary(&QueryAnswer::GetSnip20Prices { prices })
+}
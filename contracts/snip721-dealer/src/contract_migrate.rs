@@ -1,22 +1,42 @@
-use cosmwasm_std::{entry_point, DepsMut, Empty, Env, ReplyOn, Response, StdResult};
-use cw_migratable_contract_std::execute::create_broadcast_migration_complete_notification_msgs;
-use cw_migratable_contract_std::state::MIGRATION_COMPLETE_EVENT_SUBSCRIBERS;
+use cosmwasm_std::{entry_point, DepsMut, Env, Response, StdResult, Storage};
+use migration_utils::migration_complete_notifications::notify_migration_complete_event_subscribers;
+use migration_utils::version::{assert_can_migrate, save_contract_version};
+
+use crate::msg::MigrateMsg;
+use crate::state::{MigrationRecord, CURRENT_CODE_HASH, MIGRATION_HISTORY};
+use crate::version::{PKG_NAME, PKG_VERSION};
 
 #[entry_point]
-pub fn migrate(deps: DepsMut, env: Env, _msg: Empty) -> StdResult<Response> {
-    let contracts_to_notify = MIGRATION_COMPLETE_EVENT_SUBSCRIBERS
-        .load(deps.storage)
-        .unwrap_or_default()
-        .into_iter()
-        .map(|c| c.into_humanized(deps.api))
-        .collect::<StdResult<Vec<_>>>()?;
-    let msgs = create_broadcast_migration_complete_notification_msgs(
-        deps.as_ref(),
-        ReplyOn::Never,
-        0,
-        &env.contract,
-        contracts_to_notify,
-        None,
-    )?;
-    Ok(Response::new().add_submessages(msgs))
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    let mut response = Response::new();
+    match msg {
+        MigrateMsg::V1 { reason, force } => {
+            assert_can_migrate(deps.storage, PKG_NAME, PKG_VERSION, force.unwrap_or(false))?;
+            if let Some(reason) = &reason {
+                response = response.add_attribute("reason", reason);
+            }
+            save_migration_record(deps.storage, &env, reason)?;
+        }
+    }
+    save_contract_version(deps.storage, PKG_NAME, PKG_VERSION)?;
+
+    let msgs = notify_migration_complete_event_subscribers(deps, &env.contract)?;
+    Ok(response.add_submessages(msgs))
+}
+
+/// Appends a record of this migration to MIGRATION_HISTORY and saves the code hash migrated to
+fn save_migration_record(
+    storage: &mut dyn Storage,
+    env: &Env,
+    reason: Option<String>,
+) -> StdResult<()> {
+    let record = MigrationRecord {
+        previous_code_hash: CURRENT_CODE_HASH.may_load(storage)?,
+        new_code_hash: env.contract.code_hash.clone(),
+        block_height: env.block.height,
+        block_time: env.block.time,
+        reason,
+    };
+    MIGRATION_HISTORY.push(storage, &record)?;
+    CURRENT_CODE_HASH.save(storage, &env.contract.code_hash)
 }
@@ -3,7 +3,9 @@ pub mod contract_migrate;
 pub mod msg;
 mod msg_types;
 mod msg_untagged_deserializer;
+mod payees;
 pub mod state;
+mod version;
 
 mod msg_external;
 mod test_execute;
@@ -1,25 +1,68 @@
-use cosmwasm_std::{Coin, ContractInfo};
+use cosmwasm_std::{Binary, Coin, ContractInfo, Timestamp, Uint128};
 use cw_migratable_contract_std::msg::{MigratableExecuteMsg, MigrationListenerExecuteMsg};
+use migration_utils::migration_complete_notifications::MigrationCompleteNotificationFailure;
+use migration_utils::version::ContractVersion;
 use schemars::JsonSchema;
+use secret_toolkit::permit::Permit;
 use serde::{Deserialize, Serialize};
 use snip721_reference_impl::royalties::RoyaltyInfo;
 use snip721_reference_impl::token::Metadata;
 
+use crate::state::{MigrationRecord, PurchasableMetadata};
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct InstantiateMsg {
-    /// the code hash used to instantiate this contract's child snip721 contract
-    pub snip721_code_hash: String,
-    /// the code hash used to instantiate this contract's child snip721 contract
-    pub snip721_code_id: u64,
-    /// the label used to instantiate this contract's child snip721 contract
-    pub snip721_label: String,
+    /// the code hash used to instantiate this contract's child snip721 contract. Required unless
+    /// existing_snip721 is set
+    pub snip721_code_hash: Option<String>,
+    /// the code id used to instantiate this contract's child snip721 contract. Required unless
+    /// existing_snip721 is set
+    pub snip721_code_id: Option<u64>,
+    /// the label used to instantiate this contract's child snip721 contract. Required unless
+    /// existing_snip721 is set
+    pub snip721_label: Option<String>,
+    /// optional name of this contract's child snip721 contract, "PurchasableSnip721" if missing
+    pub snip721_name: Option<String>,
+    /// optional symbol of this contract's child snip721 contract, "PUR721" if missing
+    pub snip721_symbol: Option<String>,
+    /// optional config of this contract's child snip721 contract
+    pub snip721_config: Option<ChildSnip721Config>,
+    /// optional existing snip721-migratable contract to adopt as this contract's child instead of
+    /// instantiating one. None of the snip721 fields above can be set along with it, and its admin
+    /// must add this contract as a minter before ConfirmMinter can enable purchases
+    pub existing_snip721: Option<ContractInfo>,
     /// Allowed Coin prices for purchasing a mint
     pub prices: Vec<Coin>,
+    /// optional prices for purchasing a mint with SNIP-20 tokens, one per token contract
+    pub snip20_prices: Option<Vec<Snip20Price>>,
+    /// optional payees that purchase proceeds are split between, the admin receives everything if
+    /// missing
+    pub payees: Option<Vec<Payee>>,
+    /// optional flag to hold purchase proceeds in this contract until payees withdraw them,
+    /// proceeds are sent to the payees on every purchase if missing or false
+    pub hold_proceeds: Option<bool>,
+    /// optional maximum number of mints that can be purchased in the first mint run, unlimited if
+    /// missing
+    pub max_supply: Option<u32>,
+    /// optional template for the token ids of purchased mints, where {serial} is replaced by the
+    /// serial number and {mint_run} by the mint run. Any other {...} placeholder is rejected. The
+    /// child snip721 picks token ids if missing
+    pub token_id_template: Option<String>,
+    /// optional maximum number of mints an address can purchase, unlimited if missing
+    pub max_mints_per_address: Option<u32>,
+    /// optional time the sale starts, mints can be purchased right away if missing
+    pub sale_start: Option<SaleTime>,
+    /// optional time the sale ends, the sale never ends if missing
+    pub sale_end: Option<SaleTime>,
     /// optional public metadata that can be seen by everyone
     pub public_metadata: Option<Metadata>,
     /// optional private metadata that can only be seen by the owner and whitelist
     pub private_metadata: Option<Metadata>,
+    /// optional flag to mint metadata drawn at random from a pool the admin uploads with
+    /// AddToMetadataPool instead of public_metadata and private_metadata. The sale ends when the
+    /// pool is exhausted
+    pub random_metadata_pool: Option<bool>,
 
     // Selected fields from Snip721InstantiateMsg below
     /// optional admin address, env.message.sender if missing
@@ -31,7 +74,7 @@ pub struct InstantiateMsg {
     pub royalty_info: Option<RoyaltyInfo>,
 }
 
-#[derive(Serialize, Clone, Debug, Eq, PartialEq, JsonSchema)]
+#[derive(Serialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[serde(untagged)]
 pub enum ExecuteMsg {
@@ -40,25 +83,300 @@ pub enum ExecuteMsg {
     MigrateListener(MigrationListenerExecuteMsg),
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum DealerExecuteMsg {
-    // Purchase a nft mint
-    PurchaseMint {},
+    // Purchase nft mints
+    PurchaseMint {
+        /// optional number of mints to purchase, 1 if missing. The price of each must be sent
+        quantity: Option<u32>,
+        /// optional voucher signed by the presale's voucher key that allows the sender to purchase
+        /// during the presale
+        voucher: Option<Voucher>,
+    },
+    /// RetryFailedMigrationCompleteNotifications re-sends the migration complete notification to
+    /// every subscriber that could not be notified. Only the admin may call it
+    RetryFailedMigrationCompleteNotifications {},
+    /// UnsubscribeFromMigrationCompleteEvent removes `address` from the migration complete event
+    /// subscribers and frees its slot. Only the subscriber itself or the admin may call it
+    UnsubscribeFromMigrationCompleteEvent { address: String },
+    /// SetPrices replaces the allowed Coin prices for purchasing a mint. Only the admin may call it
+    SetPrices { prices: Vec<Coin> },
+    /// ProposeAdmin proposes `address` as the new admin of this contract and its child snip721.
+    /// The proposal only takes effect once `address` sends AcceptAdmin. Only the admin may call it.
+    /// Before `address` accepts, the admin must make this contract the admin of the child snip721
+    /// by sending ChangeAdmin to the child snip721, so this contract can hand it over
+    ProposeAdmin { address: String },
+    /// AcceptAdmin makes the sender the admin of this contract and sends ChangeAdmin to the child
+    /// snip721 to make the sender its admin too. It fails unless this contract is the child
+    /// snip721's admin. Only the proposed admin may call it
+    AcceptAdmin {},
+    /// SetPayees replaces the payees that purchase proceeds are split between. Only the admin may
+    /// call it
+    SetPayees { payees: Vec<Payee> },
+    /// Withdraw sends `amount` of the sender's held proceeds in `denom` to the sender, or all of
+    /// them if `amount` is missing
+    Withdraw {
+        denom: String,
+        amount: Option<Uint128>,
+    },
+    /// SetPresale replaces the presale that runs before the public sale opens, or removes it if
+    /// missing. Only the admin may call it
+    SetPresale { presale: Option<Presale> },
+    /// AddToAllowlist allows `addresses` to purchase during the presale. Only the admin may call it
+    AddToAllowlist { addresses: Vec<String> },
+    /// RemoveFromAllowlist removes `addresses` from the presale allowlist. Only the admin may call
+    /// it
+    RemoveFromAllowlist { addresses: Vec<String> },
+    /// SetSaleWindow replaces the times the sale starts and ends. Only the admin may call it
+    SetSaleWindow {
+        sale_start: Option<SaleTime>,
+        sale_end: Option<SaleTime>,
+    },
+    /// CreateViewingKey creates a viewing key for the sender from `entropy`
+    CreateViewingKey { entropy: String },
+    /// SetViewingKey sets the sender's viewing key
+    SetViewingKey { key: String },
+    /// RevokePermit revokes the sender's permit named `permit_name`
+    RevokePermit { permit_name: String },
+    /// StartMintRun starts the next mint run, whose serial numbers count from 1 again, with
+    /// `max_supply` mints for sale, unlimited if missing. Only the admin may call it
+    StartMintRun { max_supply: Option<u32> },
+    /// AddToMetadataPool appends a batch of metadata to the random metadata pool. Only the admin
+    /// may call it
+    AddToMetadataPool { metadata: Vec<PurchasableMetadata> },
+    /// ConfirmMinter checks that this contract is a minter of an adopted child snip721 and
+    /// enables purchases once it is
+    ConfirmMinter {},
+    /// Receive is called by a SNIP-20 token contract when its tokens are sent to this contract.
+    /// `msg` is an optional ReceiveMsg, sending the price of one mint purchases it for `from`
+    Receive {
+        sender: String,
+        from: String,
+        amount: Uint128,
+        memo: Option<String>,
+        msg: Option<Binary>,
+    },
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteAnswer {
+    ViewingKey { key: String },
+}
+
+/// The msg sent along with SNIP-20 tokens to this contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    /// PurchaseMint purchases mints for the owner of the sent tokens. `quantity` is 1 if missing
+    PurchaseMint { quantity: Option<u32> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     /// GetPrices returns the purchase price in acceptable coin types.
     GetPrices {},
+    /// GetSnip20Prices returns the purchase price in each acceptable SNIP-20 token
+    GetSnip20Prices {},
     GetChildSnip721 {},
+    /// MintCounts returns how many mints have been purchased in the current mint run and how many
+    /// remain
+    MintCounts {},
+    /// SaleStatus returns whether the sale is upcoming, in its presale, active or ended
+    SaleStatus {},
+    /// Presale returns the presale that runs before the public sale opens
+    Presale {},
+    /// PurchaseCount returns how many mints `address` has purchased. Requires its viewing key
+    PurchaseCount {
+        address: String,
+        viewing_key: String,
+    },
+    /// MetadataPoolSize returns how many metadata remain in the random metadata pool. Requires the
+    /// admin's viewing key
+    MetadataPoolSize {
+        address: String,
+        viewing_key: String,
+    },
+    /// WithPermit runs `query` as the address that signed `permit`
+    WithPermit {
+        permit: Permit,
+        query: QueryWithPermit,
+    },
+    /// GetPayees returns the payees that purchase proceeds are split between
+    GetPayees {},
+    /// Balances returns the purchase proceeds held for `address` in every denom. Requires its
+    /// viewing key
+    Balances {
+        address: String,
+        viewing_key: String,
+    },
+    /// ContractVersion returns the name and version of the code that last wrote this contract's
+    /// storage
+    ContractVersion {},
+    /// FailedMigrationCompleteNotifications returns the subscribers that could not be notified
+    /// that this contract migrated
+    FailedMigrationCompleteNotifications {},
+    /// MigrationHistory returns a page of the records of this contract's migrations, oldest first
+    MigrationHistory {
+        page: u32,
+        page_size: u32,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryWithPermit {
+    /// PurchaseCount returns how many mints the permit signer has purchased. Requires the owner
+    /// permission
+    PurchaseCount {},
+    /// MetadataPoolSize returns how many metadata remain in the random metadata pool. Requires a
+    /// permit signed by the admin
+    MetadataPoolSize {},
+    /// Balances returns the purchase proceeds held for the permit signer in every denom. Requires
+    /// the owner permission
+    Balances {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryAnswer {
     // GetPrices returns the purchase price in acceptable coin types.
-    GetPrices { prices: Vec<Coin> },
+    GetPrices {
+        prices: Vec<Coin>,
+    },
+    GetSnip20Prices {
+        prices: Vec<Snip20Price>,
+    },
     ContractInfo(ContractInfo),
+    MintCounts {
+        /// the current mint run, starting at 1
+        mint_run: u32,
+        minted: u32,
+        /// the maximum number of mints that can be purchased, unlimited if missing
+        max_supply: Option<u32>,
+        /// the number of mints that can still be purchased, unlimited if missing
+        remaining: Option<u32>,
+    },
+    SaleStatus {
+        status: SaleStatus,
+        sale_start: Option<SaleTime>,
+        sale_end: Option<SaleTime>,
+    },
+    Presale {
+        presale: Option<Presale>,
+    },
+    PurchaseCount {
+        count: u32,
+        /// the maximum number of mints an address can purchase, unlimited if missing
+        max_mints_per_address: Option<u32>,
+    },
+    MetadataPoolSize {
+        size: u32,
+    },
+    GetPayees {
+        payees: Vec<Payee>,
+    },
+    Balances {
+        balances: Vec<Coin>,
+    },
+    ContractVersion(ContractVersion),
+    FailedMigrationCompleteNotifications {
+        failures: Vec<MigrationCompleteNotificationFailure>,
+    },
+    MigrationHistory {
+        records: Vec<MigrationRecord>,
+        /// the total number of migrations recorded
+        count: u32,
+    },
+}
+
+/// A point in time given as a block height or a block time
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SaleTime {
+    AtHeight(u64),
+    AtTime(Timestamp),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SaleStatus {
+    /// the sale has not started yet
+    Upcoming,
+    /// only allowlisted addresses can purchase mints
+    Presale,
+    /// anyone can purchase mints
+    Active,
+    /// the sale has ended
+    Ended,
+}
+
+/// The InstantiateConfig of the child snip721. Fields that are missing default to a public token
+/// supply, a public owner and burn disabled, or the snip721's own default for the rest
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct ChildSnip721Config {
+    /// optional indicator to display token count publicly, true if missing
+    pub public_token_supply: Option<bool>,
+    /// optional indicator to display token ownership publicly, true if missing
+    pub public_owner: Option<bool>,
+    /// optional indicator to enable sealed metadata
+    pub enable_sealed_metadata: Option<bool>,
+    /// optional indicator to keep unwrapped sealed metadata private
+    pub unwrapped_metadata_is_private: Option<bool>,
+    /// optional indicator to allow the minter to update metadata
+    pub minter_may_update_metadata: Option<bool>,
+    /// optional indicator to allow the owner to update metadata
+    pub owner_may_update_metadata: Option<bool>,
+    /// optional indicator to enable burn, false if missing
+    pub enable_burn: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Presale {
+    /// the time the public sale opens and the presale ends
+    pub public_sale_start: SaleTime,
+    /// Allowed Coin prices for purchasing a mint during the presale
+    pub prices: Vec<Coin>,
+    /// optional maximum number of mints an address can purchase during the presale, unlimited if
+    /// missing
+    pub max_mints_per_address: Option<u32>,
+    /// optional compressed secp256k1 public key whose signed vouchers allow purchasing during the
+    /// presale
+    pub voucher_public_key: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Voucher {
+    /// secp256k1 signature by the presale's voucher key of the sha256 hash of
+    /// "{dealer address}:{buyer address}"
+    pub signature: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Snip20Price {
+    /// the SNIP-20 token contract
+    pub token: ContractInfo,
+    /// the amount of the token a mint costs
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Payee {
+    /// the address that receives this share of each purchase
+    pub address: String,
+    /// the share of each purchase in basis points. The shares of all payees must sum to 10000
+    pub basis_points: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrateMsg {
+    V1 {
+        /// optional reason for the migration
+        reason: Option<String>,
+        /// optional flag to allow migrating from a different contract or from a newer version
+        force: Option<bool>,
+    },
 }
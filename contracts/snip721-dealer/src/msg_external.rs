@@ -1,3 +1,4 @@
+use cosmwasm_std::Uint128;
 use serde::{Deserialize, Serialize};
 use snip721_reference_impl::msg::InstantiateMsg as Snip721InstantiateMsg;
 
@@ -7,4 +8,34 @@ pub struct MigratableSnip721InstantiateMsg {
     pub instantiate: Snip721InstantiateMsg,
     // the number of contracts that can be registered to be notified of migration
     pub max_migration_complete_event_subscribers: u8,
+    // who may subscribe contracts to be notified of migration, Open if missing
+    pub migration_complete_event_subscription_policy:
+        Option<MigrationCompleteEventSubscriptionPolicy>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationCompleteEventSubscriptionPolicy {
+    /// only the admin may subscribe contracts
+    AdminOnly,
+    /// a contract may only subscribe itself
+    SelfOnly,
+    /// anyone may subscribe any contract
+    Open,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Snip20ExecuteMsg {
+    /// registers the code hash a SNIP-20 calls Receive with when tokens are sent to this contract
+    RegisterReceive {
+        code_hash: String,
+        padding: Option<String>,
+    },
+    Transfer {
+        recipient: String,
+        amount: Uint128,
+        memo: Option<String>,
+        padding: Option<String>,
+    },
 }
@@ -0,0 +1,204 @@
+use cosmwasm_std::{
+    to_binary, Addr, BankMsg, Coin, CosmosMsg, Deps, DepsMut, MessageInfo, Response, StdError,
+    StdResult, Uint128, WasmMsg,
+};
+
+use crate::msg::Payee;
+use crate::msg_external::Snip20ExecuteMsg;
+use crate::state::{StoredPayee, ADMIN, HOLD_PROCEEDS, PAYEES, PROCEEDS_BALANCES, SNIP20_PRICES};
+
+/// The basis points all payee shares must sum to
+pub(crate) const TOTAL_BASIS_POINTS: u16 = 10_000;
+
+/// Validates payees and converts their addresses to canonical addresses. Payees must not be
+/// empty, must not repeat an address, and their shares must be positive and sum to
+/// TOTAL_BASIS_POINTS
+pub(crate) fn canonicalize_payees(deps: Deps, payees: &[Payee]) -> StdResult<Vec<StoredPayee>> {
+    if payees.is_empty() {
+        return Err(StdError::generic_err("No payees were specified"));
+    }
+    let mut stored_payees: Vec<StoredPayee> = vec![];
+    let mut total_basis_points: u32 = 0;
+    for payee in payees {
+        if payee.basis_points == 0 {
+            return Err(StdError::generic_err(format!(
+                "Payee {} must have a share greater than 0 basis points",
+                payee.address
+            )));
+        }
+        let address = deps
+            .api
+            .addr_canonicalize(deps.api.addr_validate(payee.address.as_str())?.as_str())?;
+        if stored_payees.iter().any(|p| p.address == address) {
+            return Err(StdError::generic_err(format!(
+                "Payee {} was specified more than once",
+                payee.address
+            )));
+        }
+        total_basis_points += payee.basis_points as u32;
+        stored_payees.push(StoredPayee {
+            address,
+            basis_points: payee.basis_points,
+        });
+    }
+    if total_basis_points != TOTAL_BASIS_POINTS as u32 {
+        return Err(StdError::generic_err(format!(
+            "Payee shares must sum to {} basis points, but they sum to {}",
+            TOTAL_BASIS_POINTS, total_basis_points
+        )));
+    }
+    Ok(stored_payees)
+}
+
+/// Returns the payees purchase proceeds are split between. If none were set the admin receives
+/// every basis point
+pub(crate) fn load_payees(deps: Deps) -> StdResult<Vec<Payee>> {
+    match PAYEES.may_load(deps.storage)? {
+        Some(stored_payees) => stored_payees
+            .into_iter()
+            .map(|p| {
+                Ok(Payee {
+                    address: deps.api.addr_humanize(&p.address)?.to_string(),
+                    basis_points: p.basis_points,
+                })
+            })
+            .collect(),
+        None => Ok(vec![Payee {
+            address: deps
+                .api
+                .addr_humanize(&ADMIN.load(deps.storage)?)?
+                .to_string(),
+            basis_points: TOTAL_BASIS_POINTS,
+        }]),
+    }
+}
+
+/// Splits payment between payees by their share. Each share is rounded down and the remainder
+/// goes to the first payee, so the whole payment is always paid out. Payees whose share rounds
+/// down to nothing are left out
+pub(crate) fn split_payment(payees: &[Payee], payment: &Coin) -> Vec<(String, Coin)> {
+    let mut shares: Vec<(String, Coin)> = payees
+        .iter()
+        .map(|payee| {
+            let amount = payment
+                .amount
+                .multiply_ratio(payee.basis_points, TOTAL_BASIS_POINTS);
+            (
+                payee.address.clone(),
+                Coin {
+                    denom: payment.denom.clone(),
+                    amount,
+                },
+            )
+        })
+        .collect();
+    let paid = shares
+        .iter()
+        .fold(Uint128::zero(), |sum, (_, c)| sum + c.amount);
+    if let Some((_, first_share)) = shares.first_mut() {
+        first_share.amount += payment.amount - paid;
+    }
+    shares.retain(|(_, c)| !c.amount.is_zero());
+    shares
+}
+
+/// Splits payment between payees and returns the messages that send each their share. If
+/// proceeds are held, each share is credited to the payee instead and no messages are returned
+pub(crate) fn pay_payees(deps: DepsMut, payment: &Coin) -> StdResult<Vec<CosmosMsg>> {
+    let shares = split_payment(&load_payees(deps.as_ref())?, payment);
+    if HOLD_PROCEEDS.may_load(deps.storage)?.unwrap_or(false) {
+        credit_payees(deps, &shares)?;
+        Ok(vec![])
+    } else {
+        shares
+            .into_iter()
+            .map(|(to_address, share)| send_proceeds_msg(deps.as_ref(), to_address, share))
+            .collect()
+    }
+}
+
+/// Returns the message that sends proceeds to `to_address`. The denom of SNIP-20 proceeds is the
+/// token contract's address, those are sent with a SNIP-20 Transfer
+fn send_proceeds_msg(deps: Deps, to_address: String, proceeds: Coin) -> StdResult<CosmosMsg> {
+    let snip20_prices = SNIP20_PRICES.load(deps.storage).unwrap_or_default();
+    for snip20_price in snip20_prices {
+        let token = snip20_price.token.into_humanized(deps.api)?;
+        if token.address.as_str() == proceeds.denom {
+            return Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: token.address.to_string(),
+                code_hash: token.code_hash,
+                msg: to_binary(&Snip20ExecuteMsg::Transfer {
+                    recipient: to_address,
+                    amount: proceeds.amount,
+                    memo: None,
+                    padding: None,
+                })?,
+                funds: vec![],
+            }));
+        }
+    }
+    Ok(CosmosMsg::Bank(BankMsg::Send {
+        to_address,
+        amount: vec![proceeds],
+    }))
+}
+
+/// Adds each payee's share of a payment to the proceeds held for them
+fn credit_payees(deps: DepsMut, shares: &[(String, Coin)]) -> StdResult<()> {
+    for (address, share) in shares {
+        let balances =
+            PROCEEDS_BALANCES.add_suffix(deps.api.addr_canonicalize(address.as_str())?.as_slice());
+        let balance = balances.get(deps.storage, &share.denom).unwrap_or_default();
+        balances.insert(deps.storage, &share.denom, &(balance + share.amount))?;
+    }
+    Ok(())
+}
+
+/// Returns the proceeds held for `address` in every denom
+pub(crate) fn load_balances(deps: Deps, address: &Addr) -> StdResult<Vec<Coin>> {
+    let raw_address = deps.api.addr_canonicalize(address.as_str())?;
+    PROCEEDS_BALANCES
+        .add_suffix(raw_address.as_slice())
+        .iter(deps.storage)?
+        .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+        .collect()
+}
+
+/// Sends `amount` of the sender's held proceeds in `denom` to the sender, or all of them if
+/// `amount` is missing. `denom` is the token contract's address for SNIP-20 proceeds
+pub(crate) fn withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    amount: Option<Uint128>,
+) -> StdResult<Response> {
+    let raw_sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let balances = PROCEEDS_BALANCES.add_suffix(raw_sender.as_slice());
+    let balance = balances.get(deps.storage, &denom).unwrap_or_default();
+    let amount = amount.unwrap_or(balance);
+    if amount.is_zero() {
+        return Err(StdError::generic_err(format!(
+            "No {} proceeds to withdraw",
+            denom
+        )));
+    }
+    if amount > balance {
+        return Err(StdError::generic_err(format!(
+            "Insufficient {} proceeds: {} are held, but {} was requested",
+            denom, balance, amount
+        )));
+    }
+    if amount == balance {
+        balances.remove(deps.storage, &denom)?;
+    } else {
+        balances.insert(deps.storage, &denom, &(balance - amount))?;
+    }
+    let withdrawn = Coin { denom, amount };
+    Ok(Response::new()
+        .add_message(send_proceeds_msg(
+            deps.as_ref(),
+            info.sender.to_string(),
+            withdrawn.clone(),
+        )?)
+        .add_attribute("withdrawn", withdrawn.to_string()))
+}
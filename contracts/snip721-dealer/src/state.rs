@@ -1,22 +1,107 @@
-use cosmwasm_std::{CanonicalAddr, Coin};
-use secret_toolkit::storage::Item;
+use cosmwasm_std::{CanonicalAddr, Coin, Timestamp, Uint128};
+use cw_migratable_contract_std::state::CanonicalContractInfo;
+use schemars::JsonSchema;
+use secret_toolkit::storage::{AppendStore, Item, Keymap};
 use serde::{Deserialize, Serialize};
 use snip721_reference_impl::token::Metadata;
 
+use crate::msg::{Presale, SaleTime};
+
 /// storage for this contract's admin address:
 pub static ADMIN: Item<CanonicalAddr> = Item::new(b"admin");
+/// storage for the address proposed to become admin, until it accepts
+pub static PENDING_ADMIN: Item<CanonicalAddr> = Item::new(b"pendingAdmin");
 /// storage for the address of this contract's child snip721 contract: CodeInfo
 pub static CHILD_SNIP721_CODE_HASH: Item<String> = Item::new(b"childSnip721CodeHash");
 /// storage for the address of this contract's child snip721 contract: CanonicalAddr
 pub static CHILD_SNIP721_ADDRESS: Item<CanonicalAddr> = Item::new(b"childSnip721Addr");
+/// storage for whether this contract is a minter of its child snip721, true if missing: bool
+pub static CHILD_SNIP721_MINTER_CONFIRMED: Item<bool> = Item::new(b"childSnip721MinterConfirmed");
 pub static PURCHASE_PRICES: Item<Vec<Coin>> = Item::new(b"prices");
+/// storage for the prices for purchasing a mint with SNIP-20 tokens, one per token contract
+pub static SNIP20_PRICES: Item<Vec<StoredSnip20Price>> = Item::new(b"snip20Prices");
+/// storage for the payees that purchase proceeds are split between. The admin receives everything
+/// if missing
+pub static PAYEES: Item<Vec<StoredPayee>> = Item::new(b"payees");
+/// storage for whether purchase proceeds are held in this contract until payees withdraw them.
+/// Proceeds are sent on every purchase if missing
+pub static HOLD_PROCEEDS: Item<bool> = Item::new(b"holdProceeds");
+/// storage for the proceeds held for each payee: denom -> amount. Suffixed by the payee's
+/// CanonicalAddr
+pub static PROCEEDS_BALANCES: Keymap<String, Uint128> = Keymap::new(b"proceedsBalances");
+/// storage for the maximum number of mints that can be purchased in the current mint run.
+/// Unlimited if missing
+pub static MAX_SUPPLY: Item<u32> = Item::new(b"maxSupply");
+/// storage for the number of mints that have been purchased in the current mint run
+pub static MINTED_COUNT: Item<u32> = Item::new(b"mintedCount");
+/// storage for the current mint run, 1 if missing
+pub static MINT_RUN: Item<u32> = Item::new(b"mintRun");
+/// storage for the template the token ids of purchased mints are built from
+pub static TOKEN_ID_TEMPLATE: Item<String> = Item::new(b"tokenIdTemplate");
+/// storage for the maximum number of mints an address can purchase. Unlimited if missing
+pub static MAX_MINTS_PER_ADDRESS: Item<u32> = Item::new(b"maxMintsPerAddr");
+/// storage for the number of mints an address has purchased. Suffixed by the CanonicalAddr
+pub static PURCHASE_COUNTS: Item<u32> = Item::new(b"purchaseCounts");
+/// storage prefix for the permits revoked by each address
+pub const PREFIX_REVOKED_PERMITS: &str = "revokedPermits";
+/// storage for the time the sale starts. Mints can be purchased right away if missing
+pub static SALE_START: Item<SaleTime> = Item::new(b"saleStart");
+/// storage for the time the sale ends. The sale never ends if missing
+pub static SALE_END: Item<SaleTime> = Item::new(b"saleEnd");
+/// storage for the presale that runs before the public sale opens. There is no presale if missing
+pub static PRESALE: Item<Presale> = Item::new(b"presale");
+/// storage for whether an address may purchase during the presale. Suffixed by the CanonicalAddr
+pub static ALLOWLIST: Item<bool> = Item::new(b"allowlist");
+/// storage for the number of mints an address has purchased during the presale. Suffixed by the
+/// CanonicalAddr
+pub static PRESALE_PURCHASE_COUNTS: Item<u32> = Item::new(b"presalePurchaseCounts");
 /// storage for the PurchasableMetadata used for every purchased mint
 pub static PURCHASABLE_METADATA: Item<PurchasableMetadata> = Item::new(b"purMetadata");
+/// storage for whether purchased mints draw their metadata from METADATA_POOL: bool
+pub static RANDOM_METADATA_POOL: Item<bool> = Item::new(b"randomMetadataPool");
+/// storage for the metadata that have not been drawn yet
+pub static METADATA_POOL: AppendStore<PurchasableMetadata> = AppendStore::new(b"metadataPool");
+/// storage for the seed that is hashed with the block's random bytes to draw from METADATA_POOL
+pub static PRNG_SEED: Item<Vec<u8>> = Item::new(b"prngSeed");
+/// storage for the code hash this contract is running, used as the previous code hash on migrate
+pub static CURRENT_CODE_HASH: Item<String> = Item::new(b"currentCodeHash");
+/// append-only storage for a record of each migration of this contract
+pub static MIGRATION_HISTORY: AppendStore<MigrationRecord> = AppendStore::new(b"migrationHistory");
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PurchasableMetadata {
     /// optional public metadata that can be seen by everyone
     pub public_metadata: Option<Metadata>,
     /// optional private metadata that can only be seen by the owner and whitelist
     pub private_metadata: Option<Metadata>,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoredSnip20Price {
+    /// the SNIP-20 token contract
+    pub token: CanonicalContractInfo,
+    /// the amount of the token a mint costs
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoredPayee {
+    /// the address that receives this share of each purchase
+    pub address: CanonicalAddr,
+    /// the share of each purchase in basis points
+    pub basis_points: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrationRecord {
+    /// the code hash the contract ran before the migration. None if it was not recorded
+    pub previous_code_hash: Option<String>,
+    /// the code hash the contract was migrated to
+    pub new_code_hash: String,
+    /// the height of the block the migration happened in
+    pub block_height: u64,
+    /// the time of the block the migration happened in
+    pub block_time: Timestamp,
+    /// optional reason for the migration
+    pub reason: Option<String>,
+}
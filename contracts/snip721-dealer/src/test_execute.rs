@@ -2,16 +2,30 @@
 mod tests {
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
     use cosmwasm_std::{
-        from_binary, Api, BankMsg, CanonicalAddr, Coin, CosmosMsg, StdError, Uint128, WasmMsg,
+        from_binary, to_binary, Addr, Api, Attribute, BankMsg, Binary, CanonicalAddr, Coin,
+        ContractInfo, ContractResult, CosmosMsg, Deps, Env, ReplyOn, Response, StdError,
+        SystemResult, Uint128, WasmMsg,
+    };
+    use cw_migratable_contract_std::msg::MigratableExecuteMsg;
+    use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+    use secret_toolkit::crypto::sha_256;
+    use secret_toolkit::permit::{Permit, PermitParams, PermitSignature, PubKey, TokenPermissions};
+    use snip721_reference_impl::msg::{
+        ExecuteMsg as Snip721ExecuteMsg, QueryAnswer as Snip721QueryAnswer,
     };
-    use snip721_reference_impl::msg::ExecuteMsg as Snip721ExecuteMsg;
     use snip721_reference_impl::token::Metadata;
 
-    use crate::contract::{execute, instantiate, reply};
-    use crate::msg::{DealerExecuteMsg, ExecuteMsg, InstantiateMsg};
-    use crate::state::{PurchasableMetadata, CHILD_SNIP721_ADDRESS, CHILD_SNIP721_CODE_HASH};
+    use crate::contract::{execute, instantiate, query, reply};
+    use crate::msg::{
+        DealerExecuteMsg, ExecuteMsg, InstantiateMsg, Payee, Presale, QueryAnswer, QueryMsg,
+        QueryWithPermit, ReceiveMsg, SaleStatus, SaleTime, Snip20Price, Voucher,
+    };
+    use crate::msg_external::Snip20ExecuteMsg;
+    use crate::state::{
+        PurchasableMetadata, ADMIN, CHILD_SNIP721_ADDRESS, CHILD_SNIP721_CODE_HASH, PURCHASE_PRICES,
+    };
     use crate::test_utils::test_utils::{
-        child_snip721_address, successful_child_snip721_instantiate_reply,
+        child_snip721_address, child_snip721_code_hash, successful_child_snip721_instantiate_reply,
     };
 
     #[test]
@@ -58,7 +72,10 @@ mod tests {
         )
         .unwrap();
 
-        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {});
+        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+            quantity: None,
+            voucher: None,
+        });
         let exec_purchase_res = execute(
             deps.as_mut(),
             mock_env(),
@@ -132,7 +149,10 @@ mod tests {
                             assert_eq!(Some(mint_recipient_info.sender.to_string()), owner);
                             assert_eq!(purchasable_metadata.public_metadata, public_metadata);
                             assert_eq!(purchasable_metadata.private_metadata, private_metadata);
-                            assert_eq!(None, serial_number);
+                            let serial_number = serial_number.unwrap();
+                            assert_eq!(Some(1), serial_number.mint_run);
+                            assert_eq!(1, serial_number.serial_number);
+                            assert_eq!(None, serial_number.quantity_minted_this_run);
                             assert_eq!(None, royalty_info);
                             assert_eq!(None, transferable);
                             assert_eq!(None, memo);
@@ -173,7 +193,10 @@ mod tests {
         )
         .unwrap();
 
-        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {});
+        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+            quantity: None,
+            voucher: None,
+        });
         let exec_purchase_res = execute(
             deps.as_mut(),
             mock_env(),
@@ -224,7 +247,10 @@ mod tests {
         )
         .unwrap();
 
-        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {});
+        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+            quantity: None,
+            voucher: None,
+        });
         let exec_purchase_res = execute(
             deps.as_mut(),
             mock_env(),
@@ -275,7 +301,10 @@ mod tests {
         )
         .unwrap();
 
-        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {});
+        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+            quantity: None,
+            voucher: None,
+        });
         let exec_purchase_res = execute(
             deps.as_mut(),
             mock_env(),
@@ -326,7 +355,10 @@ mod tests {
         )
         .unwrap();
 
-        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {});
+        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+            quantity: None,
+            voucher: None,
+        });
         let exec_purchase_res = execute(
             deps.as_mut(),
             mock_env(),
@@ -377,7 +409,10 @@ mod tests {
         )
         .unwrap();
 
-        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {});
+        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+            quantity: None,
+            voucher: None,
+        });
         let exec_purchase_res = execute(
             deps.as_mut(),
             mock_env(),
@@ -428,7 +463,10 @@ mod tests {
         )
         .unwrap();
 
-        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {});
+        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+            quantity: None,
+            voucher: None,
+        });
         let exec_purchase_res = execute(
             deps.as_mut(),
             mock_env(),
@@ -478,7 +516,10 @@ mod tests {
         )
         .unwrap();
 
-        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {});
+        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+            quantity: None,
+            voucher: None,
+        });
         let exec_purchase_res = execute(
             deps.as_mut(),
             mock_env(),
@@ -535,7 +576,10 @@ mod tests {
         )
         .unwrap();
 
-        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {});
+        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+            quantity: None,
+            voucher: None,
+        });
         let exec_purchase_res = execute(
             deps.as_mut(),
             mock_env(),
@@ -592,7 +636,10 @@ mod tests {
         )
         .unwrap();
 
-        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {});
+        let exec_purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+            quantity: None,
+            voucher: None,
+        });
         let exec_purchase_res = execute(
             deps.as_mut(),
             mock_env(),
@@ -609,4 +656,1803 @@ mod tests {
             ),)
         );
     }
+
+    #[test]
+    fn set_prices_replaces_prices_and_emits_old_and_new_prices() {
+        let prices = vec![Coin {
+            amount: Uint128::new(100),
+            denom: "`uscrt`".to_string(),
+        }];
+        let new_prices = vec![
+            Coin {
+                amount: Uint128::new(150),
+                denom: "`uscrt`".to_string(),
+            },
+            Coin {
+                amount: Uint128::new(5),
+                denom: "`SCRT`".to_string(),
+            },
+        ];
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            prices,
+            admin: Some(admin_info.sender.to_string()),
+            ..InstantiateMsg::default()
+        };
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            instantiate_msg,
+        )
+        .unwrap();
+
+        let exec_set_prices_msg = ExecuteMsg::Dealer(DealerExecuteMsg::SetPrices {
+            prices: new_prices.clone(),
+        });
+        let non_admin_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minty", &[]),
+            exec_set_prices_msg.clone(),
+        );
+        assert_eq!(
+            non_admin_res.err().unwrap(),
+            StdError::generic_err(
+                "This is an admin command and can only be run from the admin address"
+            )
+        );
+
+        let res = execute(deps.as_mut(), mock_env(), admin_info, exec_set_prices_msg).unwrap();
+
+        assert_eq!(
+            vec![
+                Attribute::new("old_prices", "100`uscrt`"),
+                Attribute::new("new_prices", "150`uscrt`,5`SCRT`"),
+            ],
+            res.attributes
+        );
+        assert_eq!(
+            new_prices,
+            PURCHASE_PRICES.load(deps.as_ref().storage).unwrap()
+        );
+    }
+
+    #[test]
+    fn set_prices_fails_w_no_prices() {
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            prices: vec![Coin {
+                amount: Uint128::new(100),
+                denom: "`uscrt`".to_string(),
+            }],
+            admin: Some(admin_info.sender.to_string()),
+            ..InstantiateMsg::default()
+        };
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            instantiate_msg,
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info,
+            ExecuteMsg::Dealer(DealerExecuteMsg::SetPrices { prices: vec![] }),
+        );
+
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err("No purchase prices were specified")
+        );
+    }
+
+    #[test]
+    fn set_prices_fails_w_zero_price() {
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            prices: vec![Coin {
+                amount: Uint128::new(100),
+                denom: "`uscrt`".to_string(),
+            }],
+            admin: Some(admin_info.sender.to_string()),
+            ..InstantiateMsg::default()
+        };
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            instantiate_msg,
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info,
+            ExecuteMsg::Dealer(DealerExecuteMsg::SetPrices {
+                prices: vec![Coin {
+                    amount: Uint128::zero(),
+                    denom: "`uscrt`".to_string(),
+                }],
+            }),
+        );
+
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err("Purchase price for denom `uscrt` must be greater than 0")
+        );
+    }
+
+    #[test]
+    fn proposed_admin_accepts_and_becomes_admin_of_dealer_and_child_snip721() {
+        let admin_info = mock_info("creator", &[]);
+        let new_admin_info = mock_info("new_admin", &[]);
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            prices: vec![Coin {
+                amount: Uint128::new(100),
+                denom: "`uscrt`".to_string(),
+            }],
+            admin: Some(admin_info.sender.to_string()),
+            ..InstantiateMsg::default()
+        };
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            instantiate_msg,
+        )
+        .unwrap();
+        let child_snip721_address = child_snip721_address();
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            successful_child_snip721_instantiate_reply(child_snip721_address.as_str()),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            ExecuteMsg::Dealer(DealerExecuteMsg::ProposeAdmin {
+                address: new_admin_info.sender.to_string(),
+            }),
+        )
+        .unwrap();
+        // the admin does not change until the proposed admin accepts
+        assert_eq!(
+            deps.api
+                .addr_canonicalize(admin_info.sender.as_str())
+                .unwrap(),
+            ADMIN.load(deps.as_ref().storage).unwrap()
+        );
+        let not_pending_admin_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info,
+            ExecuteMsg::Dealer(DealerExecuteMsg::AcceptAdmin {}),
+        );
+        assert_eq!(
+            not_pending_admin_res.err().unwrap(),
+            StdError::generic_err("Only the pending admin can accept the admin role")
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            new_admin_info.clone(),
+            ExecuteMsg::Dealer(DealerExecuteMsg::AcceptAdmin {}),
+        )
+        .unwrap();
+
+        assert_eq!(
+            deps.api
+                .addr_canonicalize(new_admin_info.sender.as_str())
+                .unwrap(),
+            ADMIN.load(deps.as_ref().storage).unwrap()
+        );
+        assert_eq!(1, res.messages.len());
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                code_hash,
+                msg,
+                funds,
+            }) => {
+                assert_eq!(&child_snip721_address, contract_addr);
+                assert_eq!(&child_snip721_code_hash(), code_hash);
+                assert_eq!(&Vec::<Coin>::new(), funds);
+                match from_binary(msg).unwrap() {
+                    Snip721ExecuteMsg::ChangeAdmin { address, padding } => {
+                        assert_eq!(new_admin_info.sender.to_string(), address);
+                        assert_eq!(None, padding);
+                    }
+                    _ => panic!("unexpected"),
+                }
+            }
+            _ => panic!("unexpected"),
+        }
+    }
+
+    #[test]
+    fn propose_admin_fails_for_non_admin() {
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            prices: vec![Coin {
+                amount: Uint128::new(100),
+                denom: "`uscrt`".to_string(),
+            }],
+            admin: Some(admin_info.sender.to_string()),
+            ..InstantiateMsg::default()
+        };
+        instantiate(deps.as_mut(), mock_env(), admin_info, instantiate_msg).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minty", &[]),
+            ExecuteMsg::Dealer(DealerExecuteMsg::ProposeAdmin {
+                address: "minty".to_string(),
+            }),
+        );
+
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err(
+                "This is an admin command and can only be run from the admin address"
+            )
+        );
+    }
+
+    #[test]
+    fn purchase_and_mint_splits_payment_between_payees_w_remainder_to_first_payee() {
+        let prices = vec![Coin {
+            amount: Uint128::new(101),
+            denom: "`uscrt`".to_string(),
+        }];
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            prices: prices.clone(),
+            payees: Some(vec![
+                Payee {
+                    address: "artist".to_string(),
+                    basis_points: 3_333,
+                },
+                Payee {
+                    address: "gallery".to_string(),
+                    basis_points: 6_667,
+                },
+            ]),
+            ..InstantiateMsg::default()
+        };
+        instantiate(deps.as_mut(), mock_env(), admin_info, instantiate_msg).unwrap();
+        let child_snip721_address = child_snip721_address();
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            successful_child_snip721_instantiate_reply(child_snip721_address.as_str()),
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minty", &prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: None,
+            }),
+        )
+        .unwrap();
+
+        // one Bank Send per payee followed by the mint
+        assert_eq!(3, res.messages.len());
+        let expected_sends = vec![("artist", 34u128), ("gallery", 67u128)];
+        for (i, (expected_address, expected_amount)) in expected_sends.into_iter().enumerate() {
+            match &res.messages[i].msg {
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                    assert_eq!(expected_address, to_address);
+                    assert_eq!(
+                        &vec![Coin {
+                            amount: Uint128::new(expected_amount),
+                            denom: "`uscrt`".to_string(),
+                        }],
+                        amount
+                    );
+                }
+                _ => panic!("unexpected"),
+            }
+        }
+        assert!(matches!(
+            res.messages[2].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute { .. })
+        ));
+    }
+
+    #[test]
+    fn set_payees_fails_w_shares_not_summing_to_10000_basis_points() {
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            prices: vec![Coin {
+                amount: Uint128::new(100),
+                denom: "`uscrt`".to_string(),
+            }],
+            admin: Some(admin_info.sender.to_string()),
+            ..InstantiateMsg::default()
+        };
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            instantiate_msg,
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            ExecuteMsg::Dealer(DealerExecuteMsg::SetPayees {
+                payees: vec![
+                    Payee {
+                        address: "artist".to_string(),
+                        basis_points: 5_000,
+                    },
+                    Payee {
+                        address: "gallery".to_string(),
+                        basis_points: 4_000,
+                    },
+                ],
+            }),
+        );
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err(
+                "Payee shares must sum to 10000 basis points, but they sum to 9000"
+            )
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info,
+            ExecuteMsg::Dealer(DealerExecuteMsg::SetPayees {
+                payees: vec![
+                    Payee {
+                        address: "artist".to_string(),
+                        basis_points: 10_000,
+                    },
+                    Payee {
+                        address: "gallery".to_string(),
+                        basis_points: 0,
+                    },
+                ],
+            }),
+        );
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err("Payee gallery must have a share greater than 0 basis points")
+        );
+    }
+
+    #[test]
+    fn purchase_and_mint_holds_proceeds_until_payees_withdraw() {
+        let prices = vec![Coin {
+            amount: Uint128::new(100),
+            denom: "`uscrt`".to_string(),
+        }];
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            prices: prices.clone(),
+            payees: Some(vec![
+                Payee {
+                    address: "artist".to_string(),
+                    basis_points: 2_500,
+                },
+                Payee {
+                    address: "gallery".to_string(),
+                    basis_points: 7_500,
+                },
+            ]),
+            hold_proceeds: Some(true),
+            ..InstantiateMsg::default()
+        };
+        instantiate(deps.as_mut(), mock_env(), admin_info, instantiate_msg).unwrap();
+        let child_snip721_address = child_snip721_address();
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            successful_child_snip721_instantiate_reply(child_snip721_address.as_str()),
+        )
+        .unwrap();
+
+        for _ in 0..2 {
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("minty", &prices),
+                ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                    quantity: None,
+                    voucher: None,
+                }),
+            )
+            .unwrap();
+            // only the mint is sent, the proceeds stay in the dealer
+            assert_eq!(1, res.messages.len());
+            assert!(matches!(
+                res.messages[0].msg,
+                CosmosMsg::Wasm(WasmMsg::Execute { .. })
+            ));
+        }
+        for payee in ["artist", "gallery"] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(payee, &[]),
+                ExecuteMsg::Dealer(DealerExecuteMsg::SetViewingKey {
+                    key: format!("{}_key", payee),
+                }),
+            )
+            .unwrap();
+        }
+        let wrong_key_res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Balances {
+                address: "artist".to_string(),
+                viewing_key: "gallery_key".to_string(),
+            },
+        );
+        assert!(wrong_key_res.is_err());
+        let other_contract_permit_res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::WithPermit {
+                permit: Permit {
+                    params: PermitParams {
+                        allowed_tokens: vec!["other_contract".to_string()],
+                        permit_name: "balances".to_string(),
+                        chain_id: "pulsar-2".to_string(),
+                        permissions: vec![TokenPermissions::Owner],
+                    },
+                    signature: PermitSignature {
+                        pub_key: PubKey {
+                            r#type: "tendermint/PubKeySecp256k1".to_string(),
+                            value: Binary::from(vec![2u8; 33]),
+                        },
+                        signature: Binary::from(vec![0u8; 64]),
+                    },
+                },
+                query: QueryWithPermit::Balances {},
+            },
+        );
+        assert!(other_contract_permit_res.is_err());
+        let balances_query_answer: QueryAnswer = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Balances {
+                    address: "artist".to_string(),
+                    viewing_key: "artist_key".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            QueryAnswer::Balances {
+                balances: vec![Coin {
+                    amount: Uint128::new(50),
+                    denom: "`uscrt`".to_string(),
+                }],
+            },
+            balances_query_answer
+        );
+
+        let over_withdraw_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("artist", &[]),
+            ExecuteMsg::Dealer(DealerExecuteMsg::Withdraw {
+                denom: "`uscrt`".to_string(),
+                amount: Some(Uint128::new(51)),
+            }),
+        );
+        assert_eq!(
+            over_withdraw_res.err().unwrap(),
+            StdError::generic_err(
+                "Insufficient `uscrt` proceeds: 50 are held, but 51 was requested"
+            )
+        );
+
+        let partial_withdraw_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("artist", &[]),
+            ExecuteMsg::Dealer(DealerExecuteMsg::Withdraw {
+                denom: "`uscrt`".to_string(),
+                amount: Some(Uint128::new(20)),
+            }),
+        )
+        .unwrap();
+        let remaining_withdraw_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("artist", &[]),
+            ExecuteMsg::Dealer(DealerExecuteMsg::Withdraw {
+                denom: "`uscrt`".to_string(),
+                amount: None,
+            }),
+        )
+        .unwrap();
+        for (res, expected_amount) in [(partial_withdraw_res, 20u128), (remaining_withdraw_res, 30)]
+        {
+            assert_eq!(1, res.messages.len());
+            match &res.messages[0].msg {
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                    assert_eq!("artist", to_address);
+                    assert_eq!(
+                        &vec![Coin {
+                            amount: Uint128::new(expected_amount),
+                            denom: "`uscrt`".to_string(),
+                        }],
+                        amount
+                    );
+                }
+                _ => panic!("unexpected"),
+            }
+        }
+
+        let empty_withdraw_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("artist", &[]),
+            ExecuteMsg::Dealer(DealerExecuteMsg::Withdraw {
+                denom: "`uscrt`".to_string(),
+                amount: None,
+            }),
+        );
+        assert_eq!(
+            empty_withdraw_res.err().unwrap(),
+            StdError::generic_err("No `uscrt` proceeds to withdraw")
+        );
+        // the gallery's proceeds are untouched
+        let balances_query_answer: QueryAnswer = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Balances {
+                    address: "gallery".to_string(),
+                    viewing_key: "gallery_key".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            QueryAnswer::Balances {
+                balances: vec![Coin {
+                    amount: Uint128::new(150),
+                    denom: "`uscrt`".to_string(),
+                }],
+            },
+            balances_query_answer
+        );
+    }
+
+    #[test]
+    fn receive_snip20_price_mints_to_sender_and_transfers_proceeds_to_payees() {
+        let sscrt = ContractInfo {
+            address: Addr::unchecked("sscrt_addr"),
+            code_hash: "sscrt_code_hash".to_string(),
+        };
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            snip20_prices: Some(vec![Snip20Price {
+                token: sscrt.clone(),
+                amount: Uint128::new(100),
+            }]),
+            payees: Some(vec![
+                Payee {
+                    address: "artist".to_string(),
+                    basis_points: 4_000,
+                },
+                Payee {
+                    address: "gallery".to_string(),
+                    basis_points: 6_000,
+                },
+            ]),
+            ..InstantiateMsg::default()
+        };
+        instantiate(deps.as_mut(), mock_env(), admin_info, instantiate_msg).unwrap();
+        let child_snip721_address = child_snip721_address();
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            successful_child_snip721_instantiate_reply(child_snip721_address.as_str()),
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sscrt.address.as_str(), &[]),
+            ExecuteMsg::Dealer(DealerExecuteMsg::Receive {
+                sender: "minty".to_string(),
+                from: "minty".to_string(),
+                amount: Uint128::new(100),
+                memo: None,
+                msg: Some(to_binary(&ReceiveMsg::PurchaseMint { quantity: None }).unwrap()),
+            }),
+        )
+        .unwrap();
+
+        // one Transfer per payee followed by the mint
+        assert_eq!(3, res.messages.len());
+        let expected_transfers = vec![("artist", 40u128), ("gallery", 60u128)];
+        for (i, (expected_recipient, expected_amount)) in expected_transfers.into_iter().enumerate()
+        {
+            match &res.messages[i].msg {
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr,
+                    code_hash,
+                    msg,
+                    funds,
+                }) => {
+                    assert_eq!(sscrt.address.as_str(), contract_addr);
+                    assert_eq!(&sscrt.code_hash, code_hash);
+                    assert_eq!(&Vec::<Coin>::new(), funds);
+                    match from_binary(msg).unwrap() {
+                        Snip20ExecuteMsg::Transfer {
+                            recipient, amount, ..
+                        } => {
+                            assert_eq!(expected_recipient, recipient);
+                            assert_eq!(Uint128::new(expected_amount), amount);
+                        }
+                        _ => panic!("unexpected"),
+                    }
+                }
+                _ => panic!("unexpected"),
+            }
+        }
+        match &res.messages[2].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr, msg, ..
+            }) => {
+                assert_eq!(&child_snip721_address, contract_addr);
+                match from_binary(msg).unwrap() {
+                    Snip721ExecuteMsg::MintNft { owner, .. } => {
+                        assert_eq!(Some("minty".to_string()), owner);
+                    }
+                    _ => panic!("unexpected"),
+                }
+            }
+            _ => panic!("unexpected"),
+        }
+    }
+
+    #[test]
+    fn receive_fails_w_unaccepted_token_or_wrong_amount() {
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            snip20_prices: Some(vec![Snip20Price {
+                token: ContractInfo {
+                    address: Addr::unchecked("sscrt_addr"),
+                    code_hash: "sscrt_code_hash".to_string(),
+                },
+                amount: Uint128::new(100),
+            }]),
+            ..InstantiateMsg::default()
+        };
+        instantiate(deps.as_mut(), mock_env(), admin_info, instantiate_msg).unwrap();
+        let receive_msg = |amount: u128| {
+            ExecuteMsg::Dealer(DealerExecuteMsg::Receive {
+                sender: "minty".to_string(),
+                from: "minty".to_string(),
+                amount: Uint128::new(amount),
+                memo: None,
+                msg: None,
+            })
+        };
+
+        let unaccepted_token_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other_token_addr", &[]),
+            receive_msg(100),
+        );
+        assert_eq!(
+            unaccepted_token_res.err().unwrap(),
+            StdError::generic_err("Purchasing with token:other_token_addr is not allowed")
+        );
+
+        let wrong_amount_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sscrt_addr", &[]),
+            receive_msg(99),
+        );
+        assert_eq!(
+            wrong_amount_res.err().unwrap(),
+            StdError::generic_err("Purchase price in token sscrt_addr is 100, but 99 was sent")
+        );
+    }
+
+    #[test]
+    fn purchase_and_mint_fails_w_sold_out_once_max_supply_is_purchased() {
+        let prices = vec![Coin {
+            amount: Uint128::new(100),
+            denom: "`uscrt`".to_string(),
+        }];
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            prices: prices.clone(),
+            max_supply: Some(2),
+            ..InstantiateMsg::default()
+        };
+        instantiate(deps.as_mut(), mock_env(), admin_info, instantiate_msg).unwrap();
+        let child_snip721_address = child_snip721_address();
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            successful_child_snip721_instantiate_reply(child_snip721_address.as_str()),
+        )
+        .unwrap();
+        assert_eq!(
+            QueryAnswer::MintCounts {
+                mint_run: 1,
+                minted: 0,
+                max_supply: Some(2),
+                remaining: Some(2),
+            },
+            from_binary::<QueryAnswer>(
+                &query(deps.as_ref(), mock_env(), QueryMsg::MintCounts {}).unwrap()
+            )
+            .unwrap()
+        );
+
+        for _ in 0..2 {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("minty", &prices),
+                ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                    quantity: None,
+                    voucher: None,
+                }),
+            )
+            .unwrap();
+        }
+        let sold_out_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minty", &prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: None,
+            }),
+        );
+
+        assert_eq!(
+            sold_out_res.err().unwrap(),
+            StdError::generic_err("Sold out: all 2 mints have been purchased")
+        );
+        assert_eq!(
+            QueryAnswer::MintCounts {
+                mint_run: 1,
+                minted: 2,
+                max_supply: Some(2),
+                remaining: Some(0),
+            },
+            from_binary::<QueryAnswer>(
+                &query(deps.as_ref(), mock_env(), QueryMsg::MintCounts {}).unwrap()
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn purchase_and_mint_fails_once_address_purchased_max_mints_per_address() {
+        let prices = vec![Coin {
+            amount: Uint128::new(100),
+            denom: "`uscrt`".to_string(),
+        }];
+        let admin_info = mock_info("creator", &[]);
+        let minter_info = mock_info("minty", &prices);
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            prices: prices.clone(),
+            max_mints_per_address: Some(1),
+            ..InstantiateMsg::default()
+        };
+        instantiate(deps.as_mut(), mock_env(), admin_info, instantiate_msg).unwrap();
+        let child_snip721_address = child_snip721_address();
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            successful_child_snip721_instantiate_reply(child_snip721_address.as_str()),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            minter_info.clone(),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: None,
+            }),
+        )
+        .unwrap();
+        let over_limit_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            minter_info.clone(),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: None,
+            }),
+        );
+        assert_eq!(
+            over_limit_res.err().unwrap(),
+            StdError::generic_err("Only 1 mints can be purchased per address")
+        );
+        // another address is not limited by minty's purchases
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other_minty", &prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: None,
+            }),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            minter_info.clone(),
+            ExecuteMsg::Dealer(DealerExecuteMsg::SetViewingKey {
+                key: "minty_key".to_string(),
+            }),
+        )
+        .unwrap();
+        let wrong_key_res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PurchaseCount {
+                address: minter_info.sender.to_string(),
+                viewing_key: "wrong_key".to_string(),
+            },
+        );
+        assert!(wrong_key_res.is_err());
+        let purchase_count_query_answer: QueryAnswer = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PurchaseCount {
+                    address: minter_info.sender.to_string(),
+                    viewing_key: "minty_key".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            QueryAnswer::PurchaseCount {
+                count: 1,
+                max_mints_per_address: Some(1),
+            },
+            purchase_count_query_answer
+        );
+    }
+
+    #[test]
+    fn purchase_and_mint_only_succeeds_during_sale_window() {
+        let prices = vec![Coin {
+            amount: Uint128::new(100),
+            denom: "`uscrt`".to_string(),
+        }];
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let start_height = env.block.height + 1;
+        let instantiate_msg = InstantiateMsg {
+            prices: prices.clone(),
+            sale_start: Some(SaleTime::AtHeight(start_height)),
+            sale_end: Some(SaleTime::AtHeight(start_height + 10)),
+            ..InstantiateMsg::default()
+        };
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            instantiate_msg,
+        )
+        .unwrap();
+        let child_snip721_address = child_snip721_address();
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            successful_child_snip721_instantiate_reply(child_snip721_address.as_str()),
+        )
+        .unwrap();
+        let env_at = |height: u64| {
+            let mut env = mock_env();
+            env.block.height = height;
+            env
+        };
+        let query_sale_status = |deps: Deps, env: Env| -> SaleStatus {
+            match from_binary(&query(deps, env, QueryMsg::SaleStatus {}).unwrap()).unwrap() {
+                QueryAnswer::SaleStatus { status, .. } => status,
+                _ => panic!("unexpected"),
+            }
+        };
+
+        assert_eq!(
+            SaleStatus::Upcoming,
+            query_sale_status(deps.as_ref(), env.clone())
+        );
+        let upcoming_res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("minty", &prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: None,
+            }),
+        );
+        assert_eq!(
+            upcoming_res.err().unwrap(),
+            StdError::generic_err("The sale has not started yet")
+        );
+
+        assert_eq!(
+            SaleStatus::Active,
+            query_sale_status(deps.as_ref(), env_at(start_height))
+        );
+        execute(
+            deps.as_mut(),
+            env_at(start_height),
+            mock_info("minty", &prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: None,
+            }),
+        )
+        .unwrap();
+
+        // the admin reschedules the sale to end sooner
+        execute(
+            deps.as_mut(),
+            env_at(start_height),
+            admin_info,
+            ExecuteMsg::Dealer(DealerExecuteMsg::SetSaleWindow {
+                sale_start: Some(SaleTime::AtHeight(start_height)),
+                sale_end: Some(SaleTime::AtHeight(start_height + 1)),
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            SaleStatus::Ended,
+            query_sale_status(deps.as_ref(), env_at(start_height + 1))
+        );
+        let ended_res = execute(
+            deps.as_mut(),
+            env_at(start_height + 1),
+            mock_info("minty", &prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: None,
+            }),
+        );
+        assert_eq!(
+            ended_res.err().unwrap(),
+            StdError::generic_err("The sale has ended")
+        );
+    }
+
+    /// Signs a presale voucher that lets `buyer` purchase from `dealer` during the presale
+    fn sign_voucher(secret_key: &SecretKey, dealer: &Addr, buyer: &str) -> Voucher {
+        let voucher_hash = sha_256(format!("{}:{}", dealer, buyer).as_bytes());
+        let signature = Secp256k1::new()
+            .sign_ecdsa(&Message::from_slice(&voucher_hash).unwrap(), secret_key)
+            .serialize_compact();
+        Voucher {
+            signature: Binary::from(signature.to_vec()),
+        }
+    }
+
+    #[test]
+    fn set_presale_fails_w_zero_price() {
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let instantiate_msg = InstantiateMsg {
+            prices: vec![Coin {
+                amount: Uint128::new(100),
+                denom: "`uscrt`".to_string(),
+            }],
+            ..InstantiateMsg::default()
+        };
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            instantiate_msg,
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info,
+            ExecuteMsg::Dealer(DealerExecuteMsg::SetPresale {
+                presale: Some(Presale {
+                    public_sale_start: SaleTime::AtHeight(env.block.height + 1),
+                    prices: vec![Coin {
+                        amount: Uint128::zero(),
+                        denom: "`uscrt`".to_string(),
+                    }],
+                    max_mints_per_address: None,
+                    voucher_public_key: None,
+                }),
+            }),
+        );
+
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err("Purchase price for denom `uscrt` must be greater than 0")
+        );
+    }
+
+    #[test]
+    fn presale_only_allows_allowlisted_addresses_at_presale_prices() {
+        let prices = vec![Coin {
+            amount: Uint128::new(100),
+            denom: "`uscrt`".to_string(),
+        }];
+        let presale_prices = vec![Coin {
+            amount: Uint128::new(80),
+            denom: "`uscrt`".to_string(),
+        }];
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let public_sale_start = env.block.height + 1;
+        let voucher_secret_key = SecretKey::from_slice(&[7u8; 32]).unwrap();
+        let voucher_public_key = PublicKey::from_secret_key(&Secp256k1::new(), &voucher_secret_key);
+        let instantiate_msg = InstantiateMsg {
+            prices: prices.clone(),
+            ..InstantiateMsg::default()
+        };
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            instantiate_msg,
+        )
+        .unwrap();
+        let child_snip721_address = child_snip721_address();
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            successful_child_snip721_instantiate_reply(child_snip721_address.as_str()),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            ExecuteMsg::Dealer(DealerExecuteMsg::SetPresale {
+                presale: Some(Presale {
+                    public_sale_start: SaleTime::AtHeight(public_sale_start),
+                    prices: presale_prices.clone(),
+                    max_mints_per_address: Some(1),
+                    voucher_public_key: Some(Binary::from(voucher_public_key.serialize().to_vec())),
+                }),
+            }),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info,
+            ExecuteMsg::Dealer(DealerExecuteMsg::AddToAllowlist {
+                addresses: vec!["holder".to_string()],
+            }),
+        )
+        .unwrap();
+        match from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::SaleStatus {}).unwrap())
+            .unwrap()
+        {
+            QueryAnswer::SaleStatus { status, .. } => assert_eq!(SaleStatus::Presale, status),
+            _ => panic!("unexpected"),
+        }
+
+        // an address that is not allowlisted is rejected, even with an invalid voucher
+        let not_allowlisted_res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("minty", &presale_prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: Some(Voucher {
+                    signature: Binary::from(vec![1u8; 64]),
+                }),
+            }),
+        );
+        assert_eq!(
+            not_allowlisted_res.err().unwrap(),
+            StdError::generic_err("minty is not allowed to purchase during the presale")
+        );
+        // the public price is not accepted during the presale
+        let public_price_res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("holder", &prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: None,
+            }),
+        );
+        assert_eq!(
+            public_price_res.err().unwrap(),
+            StdError::generic_err("Purchase price in `uscrt` is 80, but 100`uscrt` was sent")
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("holder", &presale_prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: None,
+            }),
+        )
+        .unwrap();
+        let over_quota_res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("holder", &presale_prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: None,
+            }),
+        );
+        assert_eq!(
+            over_quota_res.err().unwrap(),
+            StdError::generic_err("Only 1 mints can be purchased per address during the presale")
+        );
+
+        // a voucher only admits the buyer it was signed for
+        let other_buyers_voucher_res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("minty", &presale_prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: Some(sign_voucher(
+                    &voucher_secret_key,
+                    &env.contract.address,
+                    "holder",
+                )),
+            }),
+        );
+        assert_eq!(
+            other_buyers_voucher_res.err().unwrap(),
+            StdError::generic_err("minty is not allowed to purchase during the presale")
+        );
+        // a valid voucher admits an address that is not allowlisted, within the presale quota
+        let minty_voucher = sign_voucher(&voucher_secret_key, &env.contract.address, "minty");
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("minty", &presale_prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: Some(minty_voucher.clone()),
+            }),
+        )
+        .unwrap();
+        let voucher_over_quota_res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("minty", &presale_prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: Some(minty_voucher),
+            }),
+        );
+        assert_eq!(
+            voucher_over_quota_res.err().unwrap(),
+            StdError::generic_err("Only 1 mints can be purchased per address during the presale")
+        );
+
+        // once the public sale opens anyone can purchase at the public price
+        let mut public_env = mock_env();
+        public_env.block.height = public_sale_start;
+        for buyer in ["minty", "holder"] {
+            execute(
+                deps.as_mut(),
+                public_env.clone(),
+                mock_info(buyer, &prices),
+                ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                    quantity: None,
+                    voucher: None,
+                }),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn purchase_and_mint_w_quantity_sends_one_batch_mint_for_price_times_quantity() {
+        let prices = vec![Coin {
+            amount: Uint128::new(100),
+            denom: "`uscrt`".to_string(),
+        }];
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            prices: prices.clone(),
+            max_supply: Some(4),
+            ..InstantiateMsg::default()
+        };
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            instantiate_msg,
+        )
+        .unwrap();
+        let child_snip721_address = child_snip721_address();
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            successful_child_snip721_instantiate_reply(child_snip721_address.as_str()),
+        )
+        .unwrap();
+        let purchase_msg = |quantity: u32| {
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: Some(quantity),
+                voucher: None,
+            })
+        };
+        let three_prices = vec![Coin {
+            amount: Uint128::new(300),
+            denom: "`uscrt`".to_string(),
+        }];
+
+        let underpaid_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minty", &prices),
+            purchase_msg(3),
+        );
+        assert_eq!(
+            underpaid_res.err().unwrap(),
+            StdError::generic_err("Purchase price in `uscrt` is 300, but 100`uscrt` was sent")
+        );
+        let zero_quantity_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minty", &[]),
+            purchase_msg(0),
+        );
+        assert_eq!(
+            zero_quantity_res.err().unwrap(),
+            StdError::generic_err("Quantity must be greater than 0")
+        );
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minty", &three_prices),
+            purchase_msg(3),
+        )
+        .unwrap();
+        assert_eq!(2, res.messages.len());
+        match &res.messages[0].msg {
+            CosmosMsg::Bank(BankMsg::Send { amount, .. }) => assert_eq!(&three_prices, amount),
+            _ => panic!("unexpected"),
+        }
+        match &res.messages[1].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr, msg, ..
+            }) => {
+                assert_eq!(&child_snip721_address, contract_addr);
+                match from_binary(msg).unwrap() {
+                    Snip721ExecuteMsg::BatchMintNft { mints, .. } => {
+                        assert_eq!(3, mints.len());
+                        for mint in mints {
+                            assert_eq!(Some("minty".to_string()), mint.owner);
+                        }
+                    }
+                    _ => panic!("unexpected"),
+                }
+            }
+            _ => panic!("unexpected"),
+        }
+
+        let over_supply_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minty", &three_prices),
+            purchase_msg(3),
+        );
+        assert_eq!(
+            over_supply_res.err().unwrap(),
+            StdError::generic_err("Only 1 mints remain, but 3 were requested")
+        );
+
+        // the total price is computed with checked math
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info,
+            ExecuteMsg::Dealer(DealerExecuteMsg::SetPrices {
+                prices: vec![Coin {
+                    amount: Uint128::MAX,
+                    denom: "`uscrt`".to_string(),
+                }],
+            }),
+        )
+        .unwrap();
+        let overflow_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minty", &three_prices),
+            purchase_msg(2),
+        );
+        assert!(matches!(
+            overflow_res.err().unwrap(),
+            StdError::Overflow { .. }
+        ));
+    }
+
+    #[test]
+    fn adopted_child_snip721_enables_purchases_once_minter_is_confirmed() {
+        let prices = vec![Coin {
+            amount: Uint128::new(100),
+            denom: "`uscrt`".to_string(),
+        }];
+        let admin_info = mock_info("creator", &[]);
+        let env = mock_env();
+        let mut deps = mock_dependencies();
+        let child_snip721_address = child_snip721_address();
+        let instantiate_msg = InstantiateMsg {
+            prices: prices.clone(),
+            snip721_code_hash: None,
+            snip721_code_id: None,
+            snip721_label: None,
+            existing_snip721: Some(ContractInfo {
+                address: Addr::unchecked(child_snip721_address.clone()),
+                code_hash: child_snip721_code_hash(),
+            }),
+            ..InstantiateMsg::default()
+        };
+
+        let res = instantiate(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            instantiate_msg,
+        )
+        .unwrap();
+        assert_eq!(1, res.messages.len());
+        assert_eq!(ReplyOn::Never, res.messages[0].reply_on);
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                code_hash,
+                msg,
+                ..
+            }) => {
+                assert_eq!(&child_snip721_address, contract_addr);
+                assert_eq!(&child_snip721_code_hash(), code_hash);
+                assert_eq!(
+                    MigratableExecuteMsg::SubscribeToMigrationCompleteEvent {
+                        address: env.contract.address.to_string(),
+                        code_hash: env.contract.code_hash.clone(),
+                    },
+                    from_binary(msg).unwrap()
+                );
+            }
+            _ => panic!("unexpected"),
+        }
+        assert_eq!(
+            deps.api
+                .addr_canonicalize(child_snip721_address.as_str())
+                .unwrap(),
+            CHILD_SNIP721_ADDRESS.load(&deps.storage).unwrap()
+        );
+        let purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+            quantity: None,
+            voucher: None,
+        });
+        let unconfirmed_res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("minty", &prices),
+            purchase_msg.clone(),
+        );
+        assert_eq!(
+            unconfirmed_res.err().unwrap(),
+            StdError::generic_err(
+                "Purchases are disabled until ConfirmMinter confirms this contract is a minter of the child snip721"
+            )
+        );
+
+        let minters_response =
+            |minters: Vec<Addr>| to_binary(&Snip721QueryAnswer::MinterList { minters }).unwrap();
+        let not_minter_response = minters_response(vec![Addr::unchecked("creator")]);
+        deps.querier.update_wasm(move |_| {
+            SystemResult::Ok(ContractResult::Ok(not_minter_response.clone()))
+        });
+        let not_minter_res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Dealer(DealerExecuteMsg::ConfirmMinter {}),
+        );
+        assert_eq!(
+            not_minter_res.err().unwrap(),
+            StdError::generic_err(format!(
+                "{} is not a minter of the child snip721 {}",
+                env.contract.address, child_snip721_address
+            ))
+        );
+        let minter_response = minters_response(vec![env.contract.address.clone()]);
+        deps.querier
+            .update_wasm(move |_| SystemResult::Ok(ContractResult::Ok(minter_response.clone())));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Dealer(DealerExecuteMsg::ConfirmMinter {}),
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("minty", &prices),
+            purchase_msg,
+        )
+        .unwrap();
+        match &res.messages[1].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => {
+                assert_eq!(&child_snip721_address, contract_addr)
+            }
+            _ => panic!("unexpected"),
+        }
+    }
+
+    #[test]
+    fn purchase_and_mint_draws_metadata_from_pool_until_it_is_exhausted() {
+        let prices = vec![Coin {
+            amount: Uint128::new(100),
+            denom: "`uscrt`".to_string(),
+        }];
+        let admin_info = mock_info("creator", &[]);
+        let mut env = mock_env();
+        env.block.random = Some(Binary::from(b"block_random".to_vec()));
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            prices: prices.clone(),
+            random_metadata_pool: Some(true),
+            ..InstantiateMsg::default()
+        };
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            instantiate_msg,
+        )
+        .unwrap();
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            successful_child_snip721_instantiate_reply(child_snip721_address().as_str()),
+        )
+        .unwrap();
+        let pool_metadata = |token_uri: &str| PurchasableMetadata {
+            public_metadata: Some(Metadata {
+                token_uri: Some(token_uri.to_string()),
+                extension: None,
+            }),
+            private_metadata: None,
+        };
+        let non_admin_res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("minty", &[]),
+            ExecuteMsg::Dealer(DealerExecuteMsg::AddToMetadataPool {
+                metadata: vec![pool_metadata("a")],
+            }),
+        );
+        assert_eq!(
+            non_admin_res.err().unwrap(),
+            StdError::generic_err(
+                "This is an admin command and can only be run from the admin address"
+            )
+        );
+        for batch in [vec!["a", "b"], vec!["c"]] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                admin_info.clone(),
+                ExecuteMsg::Dealer(DealerExecuteMsg::AddToMetadataPool {
+                    metadata: batch.into_iter().map(pool_metadata).collect(),
+                }),
+            )
+            .unwrap();
+        }
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            ExecuteMsg::Dealer(DealerExecuteMsg::SetViewingKey {
+                key: "key".to_string(),
+            }),
+        )
+        .unwrap();
+        let pool_size = |deps: Deps, address: &str| {
+            query(
+                deps,
+                mock_env(),
+                QueryMsg::MetadataPoolSize {
+                    address: address.to_string(),
+                    viewing_key: "key".to_string(),
+                },
+            )
+            .map(|res| from_binary::<QueryAnswer>(&res).unwrap())
+        };
+        assert_eq!(
+            QueryAnswer::MetadataPoolSize { size: 3 },
+            pool_size(deps.as_ref(), "creator").unwrap()
+        );
+        let drawn_token_uris = |res: &Response| match &res.messages[1].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => match from_binary(msg).unwrap() {
+                Snip721ExecuteMsg::MintNft {
+                    public_metadata, ..
+                } => vec![public_metadata.unwrap().token_uri.unwrap()],
+                Snip721ExecuteMsg::BatchMintNft { mints, .. } => mints
+                    .into_iter()
+                    .map(|mint| mint.public_metadata.unwrap().token_uri.unwrap())
+                    .collect(),
+                _ => panic!("unexpected"),
+            },
+            _ => panic!("unexpected"),
+        };
+
+        let two_prices = vec![Coin {
+            amount: Uint128::new(200),
+            denom: "`uscrt`".to_string(),
+        }];
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("minty", &two_prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: Some(2),
+                voucher: None,
+            }),
+        )
+        .unwrap();
+        let mut token_uris = drawn_token_uris(&res);
+        assert_eq!(
+            QueryAnswer::MetadataPoolSize { size: 1 },
+            pool_size(deps.as_ref(), "creator").unwrap()
+        );
+        let over_pool_res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("minty", &two_prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: Some(2),
+                voucher: None,
+            }),
+        );
+        assert_eq!(
+            over_pool_res.err().unwrap(),
+            StdError::generic_err("Only 1 mints remain, but 2 were requested")
+        );
+        let purchase_msg = ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+            quantity: None,
+            voucher: None,
+        });
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("minty", &prices),
+            purchase_msg.clone(),
+        )
+        .unwrap();
+        token_uris.extend(drawn_token_uris(&res));
+        token_uris.sort();
+        assert_eq!(vec!["a", "b", "c"], token_uris);
+
+        let exhausted_res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("minty", &prices),
+            purchase_msg,
+        );
+        assert_eq!(
+            exhausted_res.err().unwrap(),
+            StdError::generic_err("Sold out: the metadata pool is exhausted")
+        );
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minty", &[]),
+            ExecuteMsg::Dealer(DealerExecuteMsg::SetViewingKey {
+                key: "key".to_string(),
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            pool_size(deps.as_ref(), "minty").err().unwrap(),
+            StdError::generic_err("Only the admin can query the metadata pool size")
+        );
+    }
+
+    #[test]
+    fn purchase_and_mint_numbers_mints_in_mint_runs_w_token_id_template() {
+        let prices = vec![Coin {
+            amount: Uint128::new(100),
+            denom: "`uscrt`".to_string(),
+        }];
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+        let missing_serial_res = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            InstantiateMsg {
+                prices: prices.clone(),
+                token_id_template: Some("EDITION".to_string()),
+                ..InstantiateMsg::default()
+            },
+        );
+        assert_eq!(
+            missing_serial_res.err().unwrap(),
+            StdError::generic_err("The token id template must contain {serial}")
+        );
+        let unknown_placeholder_res = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            InstantiateMsg {
+                prices: prices.clone(),
+                token_id_template: Some("{prefix}-{serial}".to_string()),
+                ..InstantiateMsg::default()
+            },
+        );
+        assert_eq!(
+            unknown_placeholder_res.err().unwrap(),
+            StdError::generic_err("Unknown placeholder {prefix} in the token id template")
+        );
+        let instantiate_msg = InstantiateMsg {
+            prices: prices.clone(),
+            max_supply: Some(2),
+            token_id_template: Some("EDITION-{mint_run}-{serial}".to_string()),
+            ..InstantiateMsg::default()
+        };
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            instantiate_msg,
+        )
+        .unwrap();
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            successful_child_snip721_instantiate_reply(child_snip721_address().as_str()),
+        )
+        .unwrap();
+        let minted_ids_and_serials = |res: &Response| match &res.messages[1].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => match from_binary(msg).unwrap() {
+                Snip721ExecuteMsg::MintNft {
+                    token_id,
+                    serial_number,
+                    ..
+                } => vec![(token_id.unwrap(), serial_number.unwrap())],
+                Snip721ExecuteMsg::BatchMintNft { mints, .. } => mints
+                    .into_iter()
+                    .map(|mint| (mint.token_id.unwrap(), mint.serial_number.unwrap()))
+                    .collect(),
+                _ => panic!("unexpected"),
+            },
+            _ => panic!("unexpected"),
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(
+                "minty",
+                &[Coin {
+                    amount: Uint128::new(200),
+                    denom: "`uscrt`".to_string(),
+                }],
+            ),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: Some(2),
+                voucher: None,
+            }),
+        )
+        .unwrap();
+        let minted = minted_ids_and_serials(&res);
+        assert_eq!(2, minted.len());
+        for (i, (token_id, serial_number)) in minted.into_iter().enumerate() {
+            assert_eq!(format!("EDITION-1-{}", i + 1), token_id);
+            assert_eq!(Some(1), serial_number.mint_run);
+            assert_eq!(i as u32 + 1, serial_number.serial_number);
+            assert_eq!(Some(2), serial_number.quantity_minted_this_run);
+        }
+
+        let non_admin_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minty", &[]),
+            ExecuteMsg::Dealer(DealerExecuteMsg::StartMintRun { max_supply: None }),
+        );
+        assert_eq!(
+            non_admin_res.err().unwrap(),
+            StdError::generic_err(
+                "This is an admin command and can only be run from the admin address"
+            )
+        );
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info,
+            ExecuteMsg::Dealer(DealerExecuteMsg::StartMintRun { max_supply: None }),
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minty", &prices),
+            ExecuteMsg::Dealer(DealerExecuteMsg::PurchaseMint {
+                quantity: None,
+                voucher: None,
+            }),
+        )
+        .unwrap();
+        let (token_id, serial_number) = minted_ids_and_serials(&res).remove(0);
+        assert_eq!("EDITION-2-1", token_id);
+        assert_eq!(Some(2), serial_number.mint_run);
+        assert_eq!(1, serial_number.serial_number);
+        assert_eq!(None, serial_number.quantity_minted_this_run);
+        assert_eq!(
+            QueryAnswer::MintCounts {
+                mint_run: 2,
+                minted: 1,
+                max_supply: None,
+                remaining: None,
+            },
+            from_binary::<QueryAnswer>(
+                &query(deps.as_ref(), mock_env(), QueryMsg::MintCounts {}).unwrap()
+            )
+            .unwrap()
+        );
+    }
 }
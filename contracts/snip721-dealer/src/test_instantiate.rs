@@ -4,19 +4,23 @@ mod tests {
         mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
     };
     use cosmwasm_std::{
-        from_binary, Api, CanonicalAddr, Coin, CosmosMsg, Env, OwnedDeps, ReplyOn, StdError,
-        StdResult, Uint128, WasmMsg,
+        from_binary, Addr, Api, Binary, CanonicalAddr, Coin, ContractInfo, CosmosMsg, Env, Event,
+        OwnedDeps, Reply, ReplyOn, StdError, StdResult, SubMsgResponse, SubMsgResult, Uint128,
+        WasmMsg,
     };
     use cw_migratable_contract_std::msg::MigratableExecuteMsg;
+    use migration_utils::version::ContractVersion;
     use secret_toolkit::serialization::{Json, Serde};
     use snip721_reference_impl::msg::{
         ExecuteMsg, InstantiateConfig, InstantiateMsg as Snip721InstantiateMsg,
     };
     use snip721_reference_impl::token::Metadata;
 
-    use crate::contract::{instantiate, reply};
-    use crate::msg::InstantiateMsg;
-    use crate::msg_external::MigratableSnip721InstantiateMsg;
+    use crate::contract::{instantiate, query, reply};
+    use crate::msg::{ChildSnip721Config, InstantiateMsg, QueryAnswer, QueryMsg, Snip20Price};
+    use crate::msg_external::{
+        MigratableSnip721InstantiateMsg, MigrationCompleteEventSubscriptionPolicy, Snip20ExecuteMsg,
+    };
     use crate::state::{
         PurchasableMetadata, ADMIN, CHILD_SNIP721_ADDRESS, CHILD_SNIP721_CODE_HASH,
         PURCHASABLE_METADATA, PURCHASE_PRICES,
@@ -76,7 +80,7 @@ mod tests {
 
         let instantiate_msg = InstantiateMsg {
             admin: Some(admin_info.sender.to_string()),
-            snip721_code_hash: snip721_code_hash.clone(),
+            snip721_code_hash: Some(snip721_code_hash.clone()),
             prices: prices.clone(),
             private_metadata: purchasable_metadata.private_metadata.clone(),
             public_metadata: purchasable_metadata.public_metadata.clone(),
@@ -128,6 +132,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn on_instantiate_snip721_reply_reads_address_from_instantiate_response_data() {
+        let (mut deps, _, _) = instantiate_successfully().unwrap();
+        let child_snip721_address = child_snip721_address();
+        // protobuf MsgInstantiateContractResponse { contract_address: 1, data: 2 }
+        let mut data = vec![0x0a, child_snip721_address.len() as u8];
+        data.extend_from_slice(child_snip721_address.as_bytes());
+        data.extend_from_slice(&[0x12, 0x02, 0x7b, 0x7d]);
+        let data_reply = Reply {
+            id: 1u64,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![Event::new("wasm").add_attribute("action", "instantiate")],
+                data: Some(Binary::from(data)),
+            }),
+        };
+
+        reply(deps.as_mut(), mock_env(), data_reply).unwrap();
+
+        assert_eq!(
+            child_snip721_address,
+            deps.api
+                .addr_humanize(&CHILD_SNIP721_ADDRESS.load(deps.as_ref().storage).unwrap())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn on_instantiate_snip721_reply_fails_w_descriptive_errors() {
+        let (mut deps, _, _) = instantiate_successfully().unwrap();
+        let ok_reply = |events: Vec<Event>, data: Option<Binary>| Reply {
+            id: 1u64,
+            result: SubMsgResult::Ok(SubMsgResponse { events, data }),
+        };
+        let cases = vec![
+            (
+                Reply {
+                    id: 1u64,
+                    result: SubMsgResult::Err("out of gas".to_string()),
+                },
+                "Failed to instantiate the child snip721: out of gas",
+            ),
+            (
+                ok_reply(vec![], None),
+                "The child snip721 instantiate reply has neither an instantiate event nor data",
+            ),
+            (
+                ok_reply(
+                    vec![Event::new("instantiate").add_attribute("code_id", "1")],
+                    None,
+                ),
+                "The child snip721 instantiate event has no contract_address attribute",
+            ),
+            (
+                ok_reply(vec![], Some(Binary::from(vec![0x0a, 0x05, 0x61]))),
+                "The child snip721 MsgInstantiateContractResponse is malformed",
+            ),
+            (
+                ok_reply(vec![], Some(Binary::from(vec![0x12, 0x01, 0x61]))),
+                "The child snip721 MsgInstantiateContractResponse has no contract address",
+            ),
+        ];
+
+        for (failed_reply, expected_err) in cases {
+            assert_eq!(
+                reply(deps.as_mut(), mock_env(), failed_reply)
+                    .err()
+                    .unwrap(),
+                StdError::generic_err(expected_err)
+            );
+        }
+        assert!(CHILD_SNIP721_ADDRESS
+            .may_load(deps.as_ref().storage)
+            .unwrap()
+            .is_none());
+    }
+
     #[test]
     fn on_instantiate_snip721_reply_reg_on_migration_complete_notify_receiver_sub_msg_added() {
         let (mut deps, env, instantiate_new_msg) = instantiate_successfully().unwrap();
@@ -155,7 +235,10 @@ mod tests {
                     funds,
                 } => {
                     assert_eq!(&child_snip721_address, contract_addr);
-                    assert_eq!(&instantiate_new_msg.snip721_code_hash, code_hash);
+                    assert_eq!(
+                        instantiate_new_msg.snip721_code_hash.as_ref(),
+                        Some(code_hash)
+                    );
                     assert_eq!(&Vec::<Coin>::new(), funds);
                     let execute_msg: MigratableExecuteMsg = from_binary(msg).unwrap();
                     let expected_execute_msg =
@@ -198,7 +281,10 @@ mod tests {
                     funds,
                 } => {
                     assert_eq!(&child_snip721_address, contract_addr);
-                    assert_eq!(&instantiate_new_msg.snip721_code_hash, code_hash);
+                    assert_eq!(
+                        instantiate_new_msg.snip721_code_hash.as_ref(),
+                        Some(code_hash)
+                    );
                     assert_eq!(&Vec::<Coin>::new(), funds);
                     let execute_msg: ExecuteMsg = from_binary(msg).unwrap();
                     let expected_execute_msg = ExecuteMsg::ChangeAdmin {
@@ -289,6 +375,261 @@ mod tests {
         );
     }
 
+    #[test]
+    fn instantiate_with_duplicate_price_denoms_fails() {
+        let prices = vec![
+            Coin {
+                amount: Uint128::new(100),
+                denom: "`uscrt`".to_string(),
+            },
+            Coin {
+                amount: Uint128::new(200),
+                denom: "`uscrt`".to_string(),
+            },
+        ];
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+
+        let instantiate_msg = InstantiateMsg {
+            admin: Some(admin_info.sender.to_string()),
+            prices,
+            ..InstantiateMsg::default()
+        };
+        let res = instantiate(deps.as_mut(), mock_env(), admin_info, instantiate_msg);
+
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err("Multiple purchase prices were specified for denom: `uscrt`")
+        );
+    }
+
+    #[test]
+    fn instantiate_with_zero_price_fails() {
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+
+        let instantiate_msg = InstantiateMsg {
+            prices: vec![Coin {
+                amount: Uint128::zero(),
+                denom: "`uscrt`".to_string(),
+            }],
+            ..InstantiateMsg::default()
+        };
+        let res = instantiate(deps.as_mut(), mock_env(), admin_info, instantiate_msg);
+
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err("Purchase price for denom `uscrt` must be greater than 0")
+        );
+    }
+
+    #[test]
+    fn instantiate_with_zero_snip20_price_fails() {
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+
+        let instantiate_msg = InstantiateMsg {
+            prices: vec![],
+            snip20_prices: Some(vec![Snip20Price {
+                token: ContractInfo {
+                    address: Addr::unchecked("sscrt_addr"),
+                    code_hash: "sscrt_code_hash".to_string(),
+                },
+                amount: Uint128::zero(),
+            }]),
+            ..InstantiateMsg::default()
+        };
+        let res = instantiate(deps.as_mut(), mock_env(), admin_info, instantiate_msg);
+
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err("Purchase price for token sscrt_addr must be greater than 0")
+        );
+    }
+
+    #[test]
+    fn instantiate_without_snip721_code_or_existing_snip721_fails() {
+        let prices = vec![Coin {
+            amount: Uint128::new(100),
+            denom: "`uscrt`".to_string(),
+        }];
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+
+        let instantiate_msg = InstantiateMsg {
+            prices,
+            snip721_code_id: None,
+            ..InstantiateMsg::default()
+        };
+        let res = instantiate(deps.as_mut(), mock_env(), admin_info, instantiate_msg);
+
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err(
+                "snip721_code_id, snip721_code_hash and snip721_label are required unless existing_snip721 is set"
+            )
+        );
+    }
+
+    #[test]
+    fn instantiate_w_existing_snip721_and_child_snip721_name_fails() {
+        let prices = vec![Coin {
+            amount: Uint128::new(100),
+            denom: "`uscrt`".to_string(),
+        }];
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+
+        let instantiate_msg = InstantiateMsg {
+            prices,
+            snip721_code_hash: None,
+            snip721_code_id: None,
+            snip721_label: None,
+            snip721_name: Some("Genesis".to_string()),
+            existing_snip721: Some(ContractInfo {
+                address: Addr::unchecked(child_snip721_address()),
+                code_hash: "child_snip721_code_hash".to_string(),
+            }),
+            ..InstantiateMsg::default()
+        };
+        let res = instantiate(deps.as_mut(), mock_env(), admin_info, instantiate_msg);
+
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err(
+                "No snip721 code id, code hash, label, name, symbol or config can be set with existing_snip721"
+            )
+        );
+    }
+
+    #[test]
+    fn instantiate_w_existing_snip721_and_snip721_code_id_fails() {
+        let prices = vec![Coin {
+            amount: Uint128::new(100),
+            denom: "`uscrt`".to_string(),
+        }];
+        let admin_info = mock_info("creator", &[]);
+        let mut deps = mock_dependencies();
+
+        let instantiate_msg = InstantiateMsg {
+            prices,
+            snip721_code_hash: None,
+            snip721_code_id: Some(10),
+            snip721_label: None,
+            existing_snip721: Some(ContractInfo {
+                address: Addr::unchecked(child_snip721_address()),
+                code_hash: "child_snip721_code_hash".to_string(),
+            }),
+            ..InstantiateMsg::default()
+        };
+        let res = instantiate(deps.as_mut(), mock_env(), admin_info, instantiate_msg);
+
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err(
+                "No snip721 code id, code hash, label, name, symbol or config can be set with existing_snip721"
+            )
+        );
+    }
+
+    #[test]
+    fn instantiate_with_only_snip20_prices_registers_receive_with_each_token() -> StdResult<()> {
+        let snip20_prices = vec![Snip20Price {
+            token: ContractInfo {
+                address: Addr::unchecked("sscrt_addr"),
+                code_hash: "sscrt_code_hash".to_string(),
+            },
+            amount: Uint128::new(100),
+        }];
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            prices: vec![],
+            snip20_prices: Some(snip20_prices.clone()),
+            ..InstantiateMsg::default()
+        };
+        let env = mock_env();
+        let res = instantiate(
+            deps.as_mut(),
+            env.clone(),
+            admin_msg_info(),
+            instantiate_msg,
+        )?;
+
+        // the child snip721 instantiate followed by RegisterReceive
+        assert_eq!(2, res.messages.len());
+        match &res.messages[1].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                code_hash,
+                msg,
+                ..
+            }) => {
+                assert_eq!("sscrt_addr", contract_addr);
+                assert_eq!("sscrt_code_hash", code_hash);
+                match from_binary(msg)? {
+                    Snip20ExecuteMsg::RegisterReceive { code_hash, .. } => {
+                        assert_eq!(env.contract.code_hash, code_hash);
+                    }
+                    _ => panic!("unexpected"),
+                }
+            }
+            _ => panic!("unexpected"),
+        }
+        let snip20_prices_query_answer: QueryAnswer =
+            from_binary(&query(deps.as_ref(), env, QueryMsg::GetSnip20Prices {})?)?;
+        assert_eq!(
+            QueryAnswer::GetSnip20Prices {
+                prices: snip20_prices
+            },
+            snip20_prices_query_answer
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn instantiate_w_child_snip721_name_symbol_and_config_overrides_defaults() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let instantiate_msg = InstantiateMsg {
+            prices: vec![Coin {
+                amount: Uint128::new(100),
+                denom: "`uscrt`".to_string(),
+            }],
+            snip721_name: Some("Genesis".to_string()),
+            snip721_symbol: Some("GEN".to_string()),
+            snip721_config: Some(ChildSnip721Config {
+                enable_sealed_metadata: Some(true),
+                enable_burn: Some(true),
+                ..ChildSnip721Config::default()
+            }),
+            ..InstantiateMsg::default()
+        };
+        let res = instantiate(deps.as_mut(), mock_env(), admin_msg_info(), instantiate_msg)?;
+
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Instantiate { msg, .. }) => {
+                let snip721_instantiate_msg: MigratableSnip721InstantiateMsg = from_binary(msg)?;
+                let instantiate = snip721_instantiate_msg.instantiate;
+                assert_eq!("Genesis", instantiate.name);
+                assert_eq!("GEN", instantiate.symbol);
+                // fields that are not given keep their defaults
+                assert_eq!(
+                    Json::serialize(&Some(InstantiateConfig {
+                        public_token_supply: Some(true),
+                        public_owner: Some(true),
+                        enable_sealed_metadata: Some(true),
+                        unwrapped_metadata_is_private: None,
+                        minter_may_update_metadata: None,
+                        owner_may_update_metadata: None,
+                        enable_burn: Some(true),
+                    }))?,
+                    Json::serialize(&instantiate.config)?
+                );
+            }
+            _ => panic!("unexpected"),
+        }
+        Ok(())
+    }
+
     #[test]
     fn instantiate_new_adds_submessage_to_instantiate_child_snip721() -> StdResult<()> {
         let prices = vec![Coin {
@@ -329,10 +670,10 @@ mod tests {
                     funds,
                     label,
                 } => {
-                    assert_eq!(&instantiate_msg.snip721_code_id, code_id);
-                    assert_eq!(&instantiate_msg.snip721_code_hash, code_hash);
+                    assert_eq!(instantiate_msg.snip721_code_id.as_ref(), Some(code_id));
+                    assert_eq!(instantiate_msg.snip721_code_hash.as_ref(), Some(code_hash));
                     assert_eq!(&Vec::<Coin>::new(), funds);
-                    assert_eq!(&instantiate_msg.snip721_label, label);
+                    assert_eq!(instantiate_msg.snip721_label.as_ref(), Some(label));
                     let snip721_instantiate_msg: MigratableSnip721InstantiateMsg =
                         from_binary(msg).unwrap();
                     // Note:
@@ -360,6 +701,9 @@ mod tests {
                             post_init_data: None,
                         },
                         max_migration_complete_event_subscribers: 1,
+                        migration_complete_event_subscription_policy: Some(
+                            MigrationCompleteEventSubscriptionPolicy::SelfOnly,
+                        ),
                     };
                     assert_eq!(
                         Json::serialize(&expected_snip721_instantiate_msg).unwrap(),
@@ -372,4 +716,19 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn instantiate_saves_queryable_contract_version() -> StdResult<()> {
+        let (deps, env, _) = instantiate_successfully()?;
+
+        let res = query(deps.as_ref(), env, QueryMsg::ContractVersion {})?;
+
+        let answer: QueryAnswer = from_binary(&res)?;
+        let expected = QueryAnswer::ContractVersion(ContractVersion {
+            contract: "snip721-dealer".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        });
+        assert_eq!(expected, answer);
+        Ok(())
+    }
 }
@@ -2,21 +2,27 @@
 mod tests {
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
     use cosmwasm_std::{
-        from_binary, Addr, Binary, BlockInfo, Coin, ContractInfo, CosmosMsg, Deps, Empty, Env,
-        ReplyOn, StdResult, Timestamp, TransactionInfo, Uint128, WasmMsg,
+        from_binary, Addr, Binary, BlockInfo, Coin, ContractInfo, CosmosMsg, Deps, Env, Reply,
+        ReplyOn, StdError, StdResult, SubMsgResult, Timestamp, TransactionInfo, Uint128, WasmMsg,
     };
     use cw_migratable_contract_std::execute::register_to_notify_on_migration_complete;
     use cw_migratable_contract_std::msg::{MigratableExecuteMsg, MigrationListenerExecuteMsg};
     use cw_migratable_contract_std::state::{canonicalize, MIGRATION_COMPLETE_EVENT_SUBSCRIBERS};
+    use migration_utils::migration_complete_notifications::{
+        MigrationCompleteNotificationFailure, MIGRATION_COMPLETE_NOTIFICATION_REPLY_ID_OFFSET,
+    };
+    use migration_utils::version::{ContractVersion, CONTRACT_VERSION};
     use secret_toolkit::permit::{
         validate, Permit, PermitParams, PermitSignature, PubKey, TokenPermissions,
     };
     use snip721_reference_impl::token::Metadata;
 
-    use crate::contract::{execute, instantiate, reply};
+    use crate::contract::{execute, instantiate, query, reply};
     use crate::contract_migrate::migrate;
-    use crate::msg::{ExecuteMsg, InstantiateMsg};
-    use crate::state::PurchasableMetadata;
+    use crate::msg::{
+        DealerExecuteMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryAnswer, QueryMsg,
+    };
+    use crate::state::{MigrationRecord, PurchasableMetadata};
     use crate::test_utils::test_utils::{
         child_snip721_address, child_snip721_code_hash, successful_child_snip721_instantiate_reply,
     };
@@ -151,7 +157,7 @@ mod tests {
         let instantiate_msg = InstantiateMsg {
             prices: prices.clone(),
             admin: Some(admin_info.sender.to_string()),
-            snip721_code_hash: snip721_code_hash.clone(),
+            snip721_code_hash: Some(snip721_code_hash.clone()),
             private_metadata: purchasable_metadata.private_metadata.clone(),
             public_metadata: purchasable_metadata.public_metadata.clone(),
             ..InstantiateMsg::default()
@@ -173,11 +179,14 @@ mod tests {
 
         let env_0_migrated = custom_mock_env_0_migrated();
 
-        let res = migrate(deps.as_mut(), env_0_migrated.clone(), Empty::default())?;
+        let res = migrate(deps.as_mut(), env_0_migrated.clone(), MigrateMsg::default())?;
 
         assert_eq!(1, res.messages.len());
-        assert_eq!(0, res.messages[0].id);
-        assert_eq!(ReplyOn::Never, res.messages[0].reply_on);
+        assert_eq!(
+            MIGRATION_COMPLETE_NOTIFICATION_REPLY_ID_OFFSET,
+            res.messages[0].id
+        );
+        assert_eq!(ReplyOn::Error, res.messages[0].reply_on);
 
         assert_is_migration_complete_notification_msg_to_contract(
             &res.messages[0].msg,
@@ -217,7 +226,7 @@ mod tests {
         let instantiate_msg = InstantiateMsg {
             prices: prices.clone(),
             admin: Some(admin_info.sender.to_string()),
-            snip721_code_hash: snip721_code_hash.clone(),
+            snip721_code_hash: Some(snip721_code_hash.clone()),
             private_metadata: purchasable_metadata.private_metadata.clone(),
             public_metadata: purchasable_metadata.public_metadata.clone(),
             ..InstantiateMsg::default()
@@ -260,12 +269,15 @@ mod tests {
 
         let env_0_migrated = custom_mock_env_0_migrated();
 
-        let res = migrate(deps.as_mut(), env_0_migrated.clone(), Empty::default())?;
+        let res = migrate(deps.as_mut(), env_0_migrated.clone(), MigrateMsg::default())?;
 
         assert_eq!(3, res.messages.len());
-        for sub_msg in &res.messages {
-            assert_eq!(0, sub_msg.id);
-            assert_eq!(ReplyOn::Never, sub_msg.reply_on);
+        for (index, sub_msg) in res.messages.iter().enumerate() {
+            assert_eq!(
+                MIGRATION_COMPLETE_NOTIFICATION_REPLY_ID_OFFSET + index as u64,
+                sub_msg.id
+            );
+            assert_eq!(ReplyOn::Error, sub_msg.reply_on);
         }
 
         assert_is_migration_complete_notification_msg_to_contract(
@@ -334,4 +346,285 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn migrate_from_newer_version_fails_without_force() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        let instantiate_msg = InstantiateMsg {
+            prices: vec![Coin {
+                amount: Uint128::new(100),
+                denom: "`uscrt`".to_string(),
+            }],
+            ..InstantiateMsg::default()
+        };
+        instantiate(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info,
+            instantiate_msg,
+        )?;
+        CONTRACT_VERSION.save(
+            deps.as_mut().storage,
+            &ContractVersion {
+                contract: "snip721-dealer".to_string(),
+                version: "99.0.0".to_string(),
+            },
+        )?;
+
+        let res = migrate(
+            deps.as_mut(),
+            custom_mock_env_0_migrated(),
+            MigrateMsg::default(),
+        );
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err(format!(
+                "Can not downgrade snip721-dealer from version 99.0.0 to {} without force",
+                env!("CARGO_PKG_VERSION")
+            ))
+        );
+
+        migrate(
+            deps.as_mut(),
+            custom_mock_env_0_migrated(),
+            MigrateMsg::V1 {
+                reason: None,
+                force: Some(true),
+            },
+        )?;
+        assert_eq!(
+            env!("CARGO_PKG_VERSION"),
+            CONTRACT_VERSION.load(deps.as_ref().storage)?.version
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn failed_child_snip721_notification_is_recorded_and_does_not_fail_migration() -> StdResult<()>
+    {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        let instantiate_msg = InstantiateMsg {
+            prices: vec![Coin {
+                amount: Uint128::new(100),
+                denom: "`uscrt`".to_string(),
+            }],
+            ..InstantiateMsg::default()
+        };
+        let env_0 = custom_mock_env_0();
+        instantiate(deps.as_mut(), env_0.clone(), admin_info, instantiate_msg)?;
+        let child_snip721_address = child_snip721_address();
+        reply(
+            deps.as_mut(),
+            env_0,
+            successful_child_snip721_instantiate_reply(child_snip721_address.as_str()),
+        )?;
+        let env_0_migrated = custom_mock_env_0_migrated();
+        let res = migrate(deps.as_mut(), env_0_migrated.clone(), MigrateMsg::default())?;
+
+        reply(
+            deps.as_mut(),
+            env_0_migrated.clone(),
+            Reply {
+                id: res.messages[0].id,
+                result: SubMsgResult::Err("child is broken".to_string()),
+            },
+        )?;
+
+        let query_res = query(
+            deps.as_ref(),
+            env_0_migrated,
+            QueryMsg::FailedMigrationCompleteNotifications {},
+        )?;
+        let answer: QueryAnswer = from_binary(&query_res)?;
+        let expected = QueryAnswer::FailedMigrationCompleteNotifications {
+            failures: vec![MigrationCompleteNotificationFailure {
+                subscriber: ContractInfo {
+                    address: Addr::unchecked(child_snip721_address),
+                    code_hash: child_snip721_code_hash(),
+                },
+                error: "child is broken".to_string(),
+            }],
+        };
+        assert_eq!(expected, answer);
+        Ok(())
+    }
+
+    #[test]
+    fn retry_failed_migration_complete_notifications_resends_and_clears_failure() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        let instantiate_msg = InstantiateMsg {
+            prices: vec![Coin {
+                amount: Uint128::new(100),
+                denom: "`uscrt`".to_string(),
+            }],
+            ..InstantiateMsg::default()
+        };
+        let env_0 = custom_mock_env_0();
+        instantiate(
+            deps.as_mut(),
+            env_0.clone(),
+            admin_info.clone(),
+            instantiate_msg,
+        )?;
+        let child_snip721_address = child_snip721_address();
+        reply(
+            deps.as_mut(),
+            env_0,
+            successful_child_snip721_instantiate_reply(child_snip721_address.as_str()),
+        )?;
+        let env_0_migrated = custom_mock_env_0_migrated();
+        let res = migrate(deps.as_mut(), env_0_migrated.clone(), MigrateMsg::default())?;
+        reply(
+            deps.as_mut(),
+            env_0_migrated.clone(),
+            Reply {
+                id: res.messages[0].id,
+                result: SubMsgResult::Err("child is broken".to_string()),
+            },
+        )?;
+        let retry_msg =
+            ExecuteMsg::Dealer(DealerExecuteMsg::RetryFailedMigrationCompleteNotifications {});
+
+        let non_admin_res = execute(
+            deps.as_mut(),
+            env_0_migrated.clone(),
+            mock_info("not_admin", &[]),
+            retry_msg.clone(),
+        );
+        assert_eq!(
+            Err(StdError::generic_err(
+                "This is an admin command and can only be run from the admin address",
+            )),
+            non_admin_res
+        );
+
+        let res = execute(deps.as_mut(), env_0_migrated.clone(), admin_info, retry_msg)?;
+        assert_eq!(1, res.messages.len());
+        assert_eq!(
+            MIGRATION_COMPLETE_NOTIFICATION_REPLY_ID_OFFSET,
+            res.messages[0].id
+        );
+        assert_eq!(ReplyOn::Error, res.messages[0].reply_on);
+        assert_is_migration_complete_notification_msg_to_contract(
+            &res.messages[0].msg,
+            &ContractInfo {
+                address: Addr::unchecked(child_snip721_address),
+                code_hash: child_snip721_code_hash(),
+            },
+            &env_0_migrated.contract,
+        );
+        let query_res = query(
+            deps.as_ref(),
+            env_0_migrated,
+            QueryMsg::FailedMigrationCompleteNotifications {},
+        )?;
+        let answer: QueryAnswer = from_binary(&query_res)?;
+        assert_eq!(
+            QueryAnswer::FailedMigrationCompleteNotifications { failures: vec![] },
+            answer
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn only_subscriber_or_admin_can_unsubscribe_from_migration_complete_event() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        let instantiate_msg = InstantiateMsg {
+            prices: vec![Coin {
+                amount: Uint128::new(100),
+                denom: "`uscrt`".to_string(),
+            }],
+            ..InstantiateMsg::default()
+        };
+        let env_0 = custom_mock_env_0();
+        instantiate(deps.as_mut(), env_0.clone(), admin_info, instantiate_msg)?;
+        let child_snip721_address = child_snip721_address();
+        reply(
+            deps.as_mut(),
+            env_0.clone(),
+            successful_child_snip721_instantiate_reply(child_snip721_address.as_str()),
+        )?;
+        let unsubscribe_msg = || {
+            ExecuteMsg::Dealer(DealerExecuteMsg::UnsubscribeFromMigrationCompleteEvent {
+                address: child_snip721_address.to_string(),
+            })
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            env_0.clone(),
+            mock_info("someone_else", &[]),
+            unsubscribe_msg(),
+        );
+        assert_eq!(
+            Err(StdError::generic_err(
+                "Only the subscriber or the admin can unsubscribe from migration complete events",
+            )),
+            res
+        );
+
+        execute(
+            deps.as_mut(),
+            env_0,
+            mock_info(child_snip721_address.as_str(), &[]),
+            unsubscribe_msg(),
+        )?;
+        assert_eq!(
+            0,
+            MIGRATION_COMPLETE_EVENT_SUBSCRIBERS
+                .load(deps.as_ref().storage)?
+                .len()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_appends_queryable_migration_history() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        let instantiate_msg = InstantiateMsg {
+            prices: vec![Coin {
+                amount: Uint128::new(100),
+                denom: "`uscrt`".to_string(),
+            }],
+            ..InstantiateMsg::default()
+        };
+        let env_0 = custom_mock_env_0();
+        instantiate(deps.as_mut(), env_0.clone(), admin_info, instantiate_msg)?;
+        let env_0_migrated = custom_mock_env_0_migrated();
+        migrate(
+            deps.as_mut(),
+            env_0_migrated.clone(),
+            MigrateMsg::V1 {
+                reason: Some("fix purchase".to_string()),
+                force: None,
+            },
+        )?;
+
+        let res = query(
+            deps.as_ref(),
+            env_0_migrated.clone(),
+            QueryMsg::MigrationHistory {
+                page: 0,
+                page_size: 10,
+            },
+        )?;
+        let answer: QueryAnswer = from_binary(&res)?;
+        let expected = QueryAnswer::MigrationHistory {
+            records: vec![MigrationRecord {
+                previous_code_hash: Some(env_0.contract.code_hash),
+                new_code_hash: env_0_migrated.contract.code_hash,
+                block_height: env_0_migrated.block.height,
+                block_time: env_0_migrated.block.time,
+                reason: Some("fix purchase".to_string()),
+            }],
+            count: 1,
+        };
+        assert_eq!(expected, answer);
+        Ok(())
+    }
 }
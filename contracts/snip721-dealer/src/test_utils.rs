@@ -3,7 +3,7 @@ pub mod test_utils {
     use cosmwasm_std::testing::mock_info;
     use cosmwasm_std::{Event, MessageInfo, Reply, SubMsgResponse, SubMsgResult};
 
-    use crate::msg::InstantiateMsg;
+    use crate::msg::{InstantiateMsg, MigrateMsg};
 
     pub fn admin_msg_info() -> MessageInfo {
         mock_info("admin", &[])
@@ -31,16 +31,38 @@ pub mod test_utils {
     impl Default for InstantiateMsg {
         fn default() -> Self {
             InstantiateMsg {
-                snip721_code_hash: child_snip721_code_hash(),
-                snip721_code_id: 10,
-                snip721_label: "test_snip721_label".to_string(),
+                snip721_code_hash: Some(child_snip721_code_hash()),
+                snip721_code_id: Some(10),
+                snip721_label: Some("test_snip721_label".to_string()),
+                snip721_name: None,
+                snip721_symbol: None,
+                snip721_config: None,
+                existing_snip721: None,
                 prices: vec![],
+                snip20_prices: None,
+                payees: None,
+                hold_proceeds: None,
+                max_supply: None,
+                token_id_template: None,
+                max_mints_per_address: None,
+                sale_start: None,
+                sale_end: None,
                 public_metadata: None,
                 private_metadata: None,
+                random_metadata_pool: None,
                 admin: None,
                 entropy: "".to_string(),
                 royalty_info: None,
             }
         }
     }
+
+    impl Default for MigrateMsg {
+        fn default() -> Self {
+            MigrateMsg::V1 {
+                reason: None,
+                force: None,
+            }
+        }
+    }
 }
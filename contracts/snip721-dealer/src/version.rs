@@ -0,0 +1,4 @@
+/// the name of this contract, taken from the Cargo package
+pub const PKG_NAME: &str = env!("CARGO_PKG_NAME");
+/// the semver of this contract, taken from the Cargo package
+pub const PKG_VERSION: &str = env!("CARGO_PKG_VERSION");
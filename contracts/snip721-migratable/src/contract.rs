@@ -1,17 +1,32 @@
 use cosmwasm_std::{
-    entry_point, Binary, ContractInfo, Deps, DepsMut, Env, MessageInfo, Response, StdError,
-    StdResult,
+    entry_point, to_binary, Binary, ContractInfo, Deps, DepsMut, Env, MessageInfo, Reply, Response,
+    StdError, StdResult, Storage,
 };
 use cw_migratable_contract_std::execute::{
     register_to_notify_on_migration_complete, update_migrated_subscriber,
 };
 use cw_migratable_contract_std::msg::{MigratableExecuteMsg, MigrationListenerExecuteMsg};
 use cw_migratable_contract_std::state::{
-    canonicalize, REMAINING_MIGRATION_COMPLETE_EVENT_SUB_SLOTS,
+    canonicalize, MIGRATION_COMPLETE_EVENT_SUBSCRIBERS,
+    REMAINING_MIGRATION_COMPLETE_EVENT_SUB_SLOTS,
 };
-use snip721_reference_impl::msg::QueryMsg;
+use migration_utils::migration_complete_notifications::{
+    load_failed_migration_complete_notifications, on_migration_complete_notification_reply,
+    retry_failed_migration_complete_notifications, unsubscribe_from_migration_complete_event,
+    MIGRATION_COMPLETE_NOTIFICATION_REPLY_ID_OFFSET,
+};
+use migration_utils::version::{save_contract_version, CONTRACT_VERSION};
+use snip721_reference_impl::state::{load, Config, CONFIG_KEY};
 
-use crate::msg::{ExecuteMsg, InstantiateMsg};
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, QueryMsg, Snip721MigratableExecuteMsg,
+    Snip721MigratableQueryAnswer, Snip721MigratableQueryMsg,
+};
+use crate::state::{
+    MigrationCompleteEventSubscriptionPolicy, CURRENT_CODE_HASH,
+    MIGRATION_COMPLETE_EVENT_SUBSCRIPTION_POLICY, MIGRATION_HISTORY,
+};
+use crate::version::{PKG_NAME, PKG_VERSION};
 
 #[entry_point]
 pub fn instantiate(
@@ -22,6 +37,11 @@ pub fn instantiate(
 ) -> StdResult<Response> {
     REMAINING_MIGRATION_COMPLETE_EVENT_SUB_SLOTS
         .save(deps.storage, &msg.max_migration_complete_event_subscribers)?;
+    if let Some(policy) = msg.migration_complete_event_subscription_policy {
+        MIGRATION_COMPLETE_EVENT_SUBSCRIPTION_POLICY.save(deps.storage, &policy)?;
+    }
+    save_contract_version(deps.storage, PKG_NAME, PKG_VERSION)?;
+    CURRENT_CODE_HASH.save(deps.storage, &env.contract.code_hash)?;
     snip721_reference_impl::contract::instantiate(deps, env, info, msg.instantiate)
 }
 
@@ -33,6 +53,7 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
         }
         ExecuteMsg::Migrate(ext_msg) => match ext_msg {
             MigratableExecuteMsg::SubscribeToMigrationCompleteEvent { address, code_hash } => {
+                assert_may_subscribe_to_migration_complete_event(deps.as_ref(), &info, &address)?;
                 register_to_notify_on_migration_complete(deps, address, code_hash)
             }
             _ => Err(StdError::generic_err("Unsupported Migrate message")),
@@ -42,7 +63,39 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
                 update_migrated_dependency(deps, info, to)
             }
         },
+        ExecuteMsg::Snip721Migratable(snip721_migratable_msg) => match snip721_migratable_msg {
+            Snip721MigratableExecuteMsg::RetryFailedMigrationCompleteNotifications {} => {
+                assert_is_admin(deps.as_ref(), &info)?;
+                let msgs = retry_failed_migration_complete_notifications(deps, &env.contract)?;
+                Ok(Response::new().add_submessages(msgs))
+            }
+            Snip721MigratableExecuteMsg::UnsubscribeFromMigrationCompleteEvent { address } => {
+                let config: Config = load(deps.storage, CONFIG_KEY)?;
+                unsubscribe_from_migration_complete_event(deps, &info, &config.admin, address)
+            }
+            Snip721MigratableExecuteMsg::SetMaxMigrationCompleteEventSubscribers {
+                max_migration_complete_event_subscribers,
+            } => {
+                assert_is_admin(deps.as_ref(), &info)?;
+                set_max_migration_complete_event_subscribers(
+                    deps.storage,
+                    max_migration_complete_event_subscribers,
+                )?;
+                Ok(Response::new())
+            }
+        },
+    }
+}
+
+/// Returns an error if the message was not sent by the snip721 admin
+pub(crate) fn assert_is_admin(deps: Deps, info: &MessageInfo) -> StdResult<()> {
+    let config: Config = load(deps.storage, CONFIG_KEY)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.admin {
+        return Err(StdError::generic_err(
+            "This is an admin command and can only be run from the admin address",
+        ));
     }
+    Ok(())
 }
 
 pub(crate) fn update_migrated_dependency(
@@ -57,7 +110,129 @@ pub(crate) fn update_migrated_dependency(
     Ok(Response::new())
 }
 
+/// Returns an error if the subscription policy does not allow the sender to subscribe `address`
+fn assert_may_subscribe_to_migration_complete_event(
+    deps: Deps,
+    info: &MessageInfo,
+    address: &str,
+) -> StdResult<()> {
+    let policy = MIGRATION_COMPLETE_EVENT_SUBSCRIPTION_POLICY
+        .may_load(deps.storage)?
+        .unwrap_or(MigrationCompleteEventSubscriptionPolicy::Open);
+    match policy {
+        MigrationCompleteEventSubscriptionPolicy::AdminOnly => {
+            let config: Config = load(deps.storage, CONFIG_KEY)?;
+            if deps.api.addr_canonicalize(info.sender.as_str())? != config.admin {
+                return Err(StdError::generic_err(
+                    "Only the admin can subscribe contracts to migration complete events",
+                ));
+            }
+        }
+        MigrationCompleteEventSubscriptionPolicy::SelfOnly => {
+            let raw_address = deps
+                .api
+                .addr_canonicalize(deps.api.addr_validate(address)?.as_str())?;
+            if deps.api.addr_canonicalize(info.sender.as_str())? != raw_address {
+                return Err(StdError::generic_err(
+                    "A contract can only subscribe itself to migration complete events",
+                ));
+            }
+        }
+        MigrationCompleteEventSubscriptionPolicy::Open => {}
+    }
+    Ok(())
+}
+
+/// Sets the remaining migration complete event subscriber slots so that the total number of
+/// subscribers is capped at `max_migration_complete_event_subscribers`
+pub(crate) fn set_max_migration_complete_event_subscribers(
+    storage: &mut dyn Storage,
+    max_migration_complete_event_subscribers: u8,
+) -> StdResult<()> {
+    let subscriber_count = MIGRATION_COMPLETE_EVENT_SUBSCRIBERS
+        .load(storage)
+        .unwrap_or_default()
+        .len();
+    if (max_migration_complete_event_subscribers as usize) < subscriber_count {
+        return Err(StdError::generic_err(format!(
+            "max_migration_complete_event_subscribers can not be less than the {} contracts already subscribed",
+            subscriber_count
+        )));
+    }
+    REMAINING_MIGRATION_COMPLETE_EVENT_SUB_SLOTS.save(
+        storage,
+        &(max_migration_complete_event_subscribers - subscriber_count as u8),
+    )
+}
+
+#[entry_point]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+    match msg.id {
+        id if id >= MIGRATION_COMPLETE_NOTIFICATION_REPLY_ID_OFFSET => {
+            on_migration_complete_notification_reply(deps, msg)
+        }
+        id => Err(StdError::generic_err(format!("Unknown reply id: {}", id))),
+    }
+}
+
 #[entry_point]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    snip721_reference_impl::contract::query(deps, env, msg)
+    match msg {
+        QueryMsg::Base(base_msg) => snip721_reference_impl::contract::query(deps, env, *base_msg),
+        QueryMsg::Snip721Migratable(snip721_migratable_msg) => match snip721_migratable_msg {
+            Snip721MigratableQueryMsg::ContractVersion {} => query_contract_version(deps),
+            Snip721MigratableQueryMsg::FailedMigrationCompleteNotifications {} => {
+                query_failed_migration_complete_notifications(deps)
+            }
+            Snip721MigratableQueryMsg::MigrationSubscribers { page, page_size } => {
+                query_migration_subscribers(deps, page, page_size)
+            }
+            Snip721MigratableQueryMsg::RemainingSubscriberSlots {} => {
+                query_remaining_subscriber_slots(deps)
+            }
+            Snip721MigratableQueryMsg::MigrationHistory { page, page_size } => {
+                query_migration_history(deps, page, page_size)
+            }
+        },
+    }
+}
+
+fn query_contract_version(deps: Deps) -> StdResult<Binary> {
+    to_binary(&Snip721MigratableQueryAnswer::ContractVersion(
+        CONTRACT_VERSION.load(deps.storage)?,
+    ))
+}
+
+fn query_failed_migration_complete_notifications(deps: Deps) -> StdResult<Binary> {
+    let failures = load_failed_migration_complete_notifications(deps)?;
+    to_binary(&Snip721MigratableQueryAnswer::FailedMigrationCompleteNotifications { failures })
+}
+
+fn query_migration_subscribers(deps: Deps, page: u32, page_size: u32) -> StdResult<Binary> {
+    let subscribers = MIGRATION_COMPLETE_EVENT_SUBSCRIBERS
+        .load(deps.storage)
+        .unwrap_or_default();
+    let count = subscribers.len() as u32;
+    let subscribers = subscribers
+        .into_iter()
+        .skip(page.saturating_mul(page_size) as usize)
+        .take(page_size as usize)
+        .map(|s| s.into_humanized(deps.api))
+        .collect::<StdResult<Vec<_>>>()?;
+    to_binary(&Snip721MigratableQueryAnswer::MigrationSubscribers { subscribers, count })
+}
+
+fn query_remaining_subscriber_slots(deps: Deps) -> StdResult<Binary> {
+    to_binary(&Snip721MigratableQueryAnswer::RemainingSubscriberSlots {
+        remaining: REMAINING_MIGRATION_COMPLETE_EVENT_SUB_SLOTS
+            .load(deps.storage)
+            .unwrap_or_default(),
+    })
+}
+
+fn query_migration_history(deps: Deps, page: u32, page_size: u32) -> StdResult<Binary> {
+    to_binary(&Snip721MigratableQueryAnswer::MigrationHistory {
+        records: MIGRATION_HISTORY.paging(deps.storage, page, page_size)?,
+        count: MIGRATION_HISTORY.get_len(deps.storage)?,
+    })
 }
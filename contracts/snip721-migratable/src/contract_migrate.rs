@@ -1,22 +1,97 @@
-use cosmwasm_std::{entry_point, DepsMut, Empty, Env, ReplyOn, Response, StdResult};
-use cw_migratable_contract_std::execute::create_broadcast_migration_complete_notification_msgs;
-use cw_migratable_contract_std::state::MIGRATION_COMPLETE_EVENT_SUBSCRIBERS;
+use cosmwasm_std::{entry_point, DepsMut, Env, Response, StdResult, Storage};
+use migration_utils::migration_complete_notifications::notify_migration_complete_event_subscribers;
+use migration_utils::version::{assert_can_migrate, save_contract_version, CONTRACT_VERSION};
+use snip721_reference_impl::msg::InstantiateConfig;
+use snip721_reference_impl::state::{load, save, Config, CONFIG_KEY};
+
+use crate::contract::set_max_migration_complete_event_subscribers;
+use crate::msg::MigrateMsg;
+use crate::state::{MigrationRecord, CURRENT_CODE_HASH, MIGRATION_HISTORY};
+use crate::state_upgrades::run_state_upgrades;
+use crate::version::{PKG_NAME, PKG_VERSION};
 
 #[entry_point]
-pub fn migrate(deps: DepsMut, env: Env, _msg: Empty) -> StdResult<Response> {
-    let contracts_to_notify = MIGRATION_COMPLETE_EVENT_SUBSCRIBERS
-        .load(deps.storage)
-        .unwrap_or_default()
-        .into_iter()
-        .map(|c| c.into_humanized(deps.api))
-        .collect::<StdResult<Vec<_>>>()?;
-    let msgs = create_broadcast_migration_complete_notification_msgs(
-        deps.as_ref(),
-        ReplyOn::Never,
-        0,
-        &env.contract,
-        contracts_to_notify,
-        None,
-    )?;
-    Ok(Response::new().add_submessages(msgs))
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    let mut response = Response::new();
+    match msg {
+        MigrateMsg::V1 {
+            reason,
+            max_migration_complete_event_subscribers,
+            config,
+            force,
+        } => {
+            assert_can_migrate(deps.storage, PKG_NAME, PKG_VERSION, force.unwrap_or(false))?;
+            // storage written by a different contract has an unknown layout, so every step runs
+            let from_version = CONTRACT_VERSION
+                .may_load(deps.storage)?
+                .filter(|stored| stored.contract == PKG_NAME)
+                .map(|stored| stored.version);
+            for version in run_state_upgrades(deps.storage, from_version.as_deref())? {
+                response = response.add_attribute("state_upgrade", version);
+            }
+            if let Some(max_migration_complete_event_subscribers) =
+                max_migration_complete_event_subscribers
+            {
+                set_max_migration_complete_event_subscribers(
+                    deps.storage,
+                    max_migration_complete_event_subscribers,
+                )?;
+            }
+            if let Some(config) = config {
+                override_config(deps.storage, config)?;
+            }
+            if let Some(reason) = &reason {
+                response = response.add_attribute("reason", reason);
+            }
+            save_migration_record(deps.storage, &env, reason)?;
+        }
+    }
+    save_contract_version(deps.storage, PKG_NAME, PKG_VERSION)?;
+
+    let msgs = notify_migration_complete_event_subscribers(deps, &env.contract)?;
+    Ok(response.add_submessages(msgs))
+}
+
+/// Appends a record of this migration to MIGRATION_HISTORY and saves the code hash migrated to
+fn save_migration_record(
+    storage: &mut dyn Storage,
+    env: &Env,
+    reason: Option<String>,
+) -> StdResult<()> {
+    let record = MigrationRecord {
+        previous_code_hash: CURRENT_CODE_HASH.may_load(storage)?,
+        new_code_hash: env.contract.code_hash.clone(),
+        block_height: env.block.height,
+        block_time: env.block.time,
+        reason,
+    };
+    MIGRATION_HISTORY.push(storage, &record)?;
+    CURRENT_CODE_HASH.save(storage, &env.contract.code_hash)
+}
+
+/// Overwrites each snip721 Config flag that is set in `overrides`
+fn override_config(storage: &mut dyn Storage, overrides: InstantiateConfig) -> StdResult<()> {
+    let mut config: Config = load(storage, CONFIG_KEY)?;
+    if let Some(public_token_supply) = overrides.public_token_supply {
+        config.token_supply_is_public = public_token_supply;
+    }
+    if let Some(public_owner) = overrides.public_owner {
+        config.owner_is_public = public_owner;
+    }
+    if let Some(enable_sealed_metadata) = overrides.enable_sealed_metadata {
+        config.sealed_metadata_is_enabled = enable_sealed_metadata;
+    }
+    if let Some(unwrapped_metadata_is_private) = overrides.unwrapped_metadata_is_private {
+        config.unwrap_to_private = unwrapped_metadata_is_private;
+    }
+    if let Some(minter_may_update_metadata) = overrides.minter_may_update_metadata {
+        config.minter_may_update_metadata = minter_may_update_metadata;
+    }
+    if let Some(owner_may_update_metadata) = overrides.owner_may_update_metadata {
+        config.owner_may_update_metadata = owner_may_update_metadata;
+    }
+    if let Some(enable_burn) = overrides.enable_burn {
+        config.burn_is_enabled = enable_burn;
+    }
+    save(storage, CONFIG_KEY, &config)
 }
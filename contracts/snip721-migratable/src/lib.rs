@@ -3,6 +3,8 @@ pub mod contract_migrate;
 pub mod msg;
 mod msg_untagged_deserializer;
 pub mod state;
+mod state_upgrades;
+mod version;
 
 mod test_instantiate;
 mod test_migrate;
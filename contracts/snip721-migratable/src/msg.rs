@@ -1,7 +1,12 @@
+use cosmwasm_std::ContractInfo;
 use cw_migratable_contract_std::msg::{MigratableExecuteMsg, MigrationListenerExecuteMsg};
+use migration_utils::migration_complete_notifications::MigrationCompleteNotificationFailure;
+use migration_utils::version::ContractVersion;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use snip721_reference_impl::msg::InstantiateMsg as Snip721InstantiateMsg;
+use snip721_reference_impl::msg::{InstantiateConfig, InstantiateMsg as Snip721InstantiateMsg};
+
+use crate::state::{MigrationCompleteEventSubscriptionPolicy, MigrationRecord};
 
 #[derive(Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -9,6 +14,9 @@ pub struct InstantiateMsg {
     pub instantiate: Snip721InstantiateMsg,
     // the number of contracts that can be registered to be notified of migration
     pub max_migration_complete_event_subscribers: u8,
+    // who may subscribe contracts to be notified of migration, Open if missing
+    pub migration_complete_event_subscription_policy:
+        Option<MigrationCompleteEventSubscriptionPolicy>,
 }
 
 #[derive(Serialize, JsonSchema)]
@@ -18,4 +26,87 @@ pub enum ExecuteMsg {
     Base(Box<snip721_reference_impl::msg::ExecuteMsg>),
     Migrate(MigratableExecuteMsg),
     MigrateListener(MigrationListenerExecuteMsg),
+    Snip721Migratable(Snip721MigratableExecuteMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Snip721MigratableExecuteMsg {
+    /// RetryFailedMigrationCompleteNotifications re-sends the migration complete notification to
+    /// every subscriber that could not be notified. Only the admin may call it
+    RetryFailedMigrationCompleteNotifications {},
+    /// UnsubscribeFromMigrationCompleteEvent removes `address` from the migration complete event
+    /// subscribers and frees its slot. Only the subscriber itself or the admin may call it
+    UnsubscribeFromMigrationCompleteEvent { address: String },
+    /// SetMaxMigrationCompleteEventSubscribers sets how many contracts in total can subscribe to
+    /// be notified of migration. Can not be less than the number of contracts already subscribed.
+    /// Only the admin may call it
+    SetMaxMigrationCompleteEventSubscribers {
+        max_migration_complete_event_subscribers: u8,
+    },
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrateMsg {
+    V1 {
+        /// optional reason for the migration
+        reason: Option<String>,
+        /// optional new number of contracts that can be registered to be notified of migration.
+        /// Can not be less than the number of contracts already registered
+        max_migration_complete_event_subscribers: Option<u8>,
+        /// optional Config flags to override. Any flag that is not set keeps its current value
+        config: Option<InstantiateConfig>,
+        /// optional flag to allow migrating from a different contract or from a newer version
+        force: Option<bool>,
+    },
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[serde(untagged)]
+pub enum QueryMsg {
+    Base(Box<snip721_reference_impl::msg::QueryMsg>),
+    Snip721Migratable(Snip721MigratableQueryMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Snip721MigratableQueryMsg {
+    /// ContractVersion returns the name and version of the code that last wrote this contract's
+    /// storage
+    ContractVersion {},
+    /// FailedMigrationCompleteNotifications returns the subscribers that could not be notified
+    /// that this contract migrated
+    FailedMigrationCompleteNotifications {},
+    /// MigrationSubscribers returns a page of the contracts that are notified when this contract
+    /// migrates
+    MigrationSubscribers { page: u32, page_size: u32 },
+    /// RemainingSubscriberSlots returns how many more contracts can subscribe to be notified when
+    /// this contract migrates
+    RemainingSubscriberSlots {},
+    /// MigrationHistory returns a page of the records of this contract's migrations, oldest first
+    MigrationHistory { page: u32, page_size: u32 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Snip721MigratableQueryAnswer {
+    ContractVersion(ContractVersion),
+    FailedMigrationCompleteNotifications {
+        failures: Vec<MigrationCompleteNotificationFailure>,
+    },
+    MigrationSubscribers {
+        subscribers: Vec<ContractInfo>,
+        /// the total number of subscribers
+        count: u32,
+    },
+    RemainingSubscriberSlots {
+        remaining: u8,
+    },
+    MigrationHistory {
+        records: Vec<MigrationRecord>,
+        /// the total number of migrations recorded
+        count: u32,
+    },
 }
@@ -2,9 +2,10 @@ use cw_migratable_contract_std::msg::{MigratableExecuteMsg, MigrationListenerExe
 use serde::de::{Error, Visitor};
 use serde::{de, Deserialize, Deserializer};
 
-use crate::msg::ExecuteMsg;
+use crate::msg::{ExecuteMsg, QueryMsg, Snip721MigratableExecuteMsg, Snip721MigratableQueryMsg};
 
 struct ExecuteMsgVisitor;
+struct QueryMsgVisitor;
 
 impl<'de> Deserialize<'de> for ExecuteMsg {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -45,7 +46,52 @@ impl<'de> Visitor<'de> for ExecuteMsgVisitor {
             return Ok(ExecuteMsg::MigrateListener(migration_listener_msg));
         }
 
+        // Attempt to deserialize into the Snip721Migratable variant
+        if let Ok(snip721_migratable_msg) =
+            cosmwasm_std::from_slice::<Snip721MigratableExecuteMsg>(v)
+        {
+            return Ok(ExecuteMsg::Snip721Migratable(snip721_migratable_msg));
+        }
+
         // If all deserialization attempts fail, return an error
         Err(de::Error::custom("Unsupported Execute message"))
     }
 }
+
+impl<'de> Deserialize<'de> for QueryMsg {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(QueryMsgVisitor)
+    }
+}
+
+impl<'de> Visitor<'de> for QueryMsgVisitor {
+    type Value = QueryMsg;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a valid QueryMsg variant")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        // Attempt to deserialize into the Base variant
+        if let Ok(base_msg) =
+            cosmwasm_std::from_slice::<Box<snip721_reference_impl::msg::QueryMsg>>(v)
+        {
+            return Ok(QueryMsg::Base(base_msg));
+        }
+
+        // Attempt to deserialize into the Snip721Migratable variant
+        if let Ok(snip721_migratable_msg) = cosmwasm_std::from_slice::<Snip721MigratableQueryMsg>(v)
+        {
+            return Ok(QueryMsg::Snip721Migratable(snip721_migratable_msg));
+        }
+
+        // If all deserialization attempts fail, return an error
+        Err(de::Error::custom("Unsupported Query message"))
+    }
+}
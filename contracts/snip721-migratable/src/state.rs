@@ -1,9 +1,20 @@
-use secret_toolkit::storage::Item;
+use cosmwasm_std::Timestamp;
+use schemars::JsonSchema;
+use secret_toolkit::storage::{AppendStore, Item};
 use serde::{Deserialize, Serialize};
 
-/// storage for MigrateInTokensProgress singleton
+/// storage for MigrateInTokensProgress singleton. Only written by v2 contracts, it is removed by
+/// the v3.0.0 state upgrade
 pub static MIGRATE_IN_TOKENS_PROGRESS: Item<MigrateInTokensProgress> =
     Item::new(b"migrateintknsprogress");
+/// storage for the code hash this contract is running, used as the previous code hash on migrate
+pub static CURRENT_CODE_HASH: Item<String> = Item::new(b"currentCodeHash");
+/// append-only storage for a record of each migration of this contract
+pub static MIGRATION_HISTORY: AppendStore<MigrationRecord> = AppendStore::new(b"migrationHistory");
+/// storage for who may subscribe contracts to migration complete events. Open if missing
+pub static MIGRATION_COMPLETE_EVENT_SUBSCRIPTION_POLICY: Item<
+    MigrationCompleteEventSubscriptionPolicy,
+> = Item::new(b"migCompleteEventSubPolicy");
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct MigrateInTokensProgress {
@@ -12,3 +23,28 @@ pub struct MigrateInTokensProgress {
     /// the next mint index out of migrate_in_mint_cnt that must be migrated
     pub migrate_in_next_mint_index: u32,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrationRecord {
+    /// the code hash the contract ran before the migration. None if it was not recorded
+    pub previous_code_hash: Option<String>,
+    /// the code hash the contract was migrated to
+    pub new_code_hash: String,
+    /// the height of the block the migration happened in
+    pub block_height: u64,
+    /// the time of the block the migration happened in
+    pub block_time: Timestamp,
+    /// optional reason for the migration
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationCompleteEventSubscriptionPolicy {
+    /// only the admin may subscribe contracts
+    AdminOnly,
+    /// a contract may only subscribe itself
+    SelfOnly,
+    /// anyone may subscribe any contract
+    Open,
+}
@@ -0,0 +1,51 @@
+use cosmwasm_std::{StdResult, Storage};
+use migration_utils::version::parse_semver;
+
+use crate::state::MIGRATE_IN_TOKENS_PROGRESS;
+use crate::version::PKG_VERSION;
+
+/// A step that upgrades storage written by code older than `version` into the layout expected
+/// by `version`. Contracts instantiated before versions were tracked run every step, so each
+/// step must be idempotent.
+struct StateUpgrade {
+    version: &'static str,
+    upgrade: fn(&mut dyn Storage) -> StdResult<()>,
+}
+
+/// The registry of storage upgrade steps, ordered by ascending version
+const STATE_UPGRADES: &[StateUpgrade] = &[StateUpgrade {
+    version: "3.0.0",
+    upgrade: remove_migrate_in_tokens_progress,
+}];
+
+/// Runs every storage upgrade step newer than `from_version` up to and including this code's
+/// version, in order. Every step is run if `from_version` is unknown.
+///
+/// Returns the versions of the steps that were run
+pub(crate) fn run_state_upgrades(
+    storage: &mut dyn Storage,
+    from_version: Option<&str>,
+) -> StdResult<Vec<&'static str>> {
+    let from_version = from_version.map(parse_semver).transpose()?;
+    let to_version = parse_semver(PKG_VERSION)?;
+    let mut applied = vec![];
+    for step in STATE_UPGRADES {
+        let step_version = parse_semver(step.version)?;
+        let is_newer = match from_version {
+            Some(from) => from < step_version,
+            None => true,
+        };
+        if is_newer && step_version <= to_version {
+            (step.upgrade)(storage)?;
+            applied.push(step.version);
+        }
+    }
+    Ok(applied)
+}
+
+/// v3.0.0 no longer migrates tokens in from another contract, so the progress of a token
+/// migration left behind by v2 contracts is dropped
+fn remove_migrate_in_tokens_progress(storage: &mut dyn Storage) -> StdResult<()> {
+    MIGRATE_IN_TOKENS_PROGRESS.remove(storage);
+    Ok(())
+}
@@ -1,8 +1,11 @@
 #[cfg(test)]
 mod tests {
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{from_binary, StdResult};
+    use migration_utils::version::ContractVersion;
 
-    use crate::contract::instantiate;
+    use crate::contract::{instantiate, query};
+    use crate::msg::{QueryMsg, Snip721MigratableQueryAnswer, Snip721MigratableQueryMsg};
     use crate::test_utils::test_utils::instantiate_msg;
 
     #[test]
@@ -21,4 +24,30 @@ mod tests {
 
         assert!(res.is_ok(),);
     }
+
+    #[test]
+    fn instantiate_saves_queryable_contract_version() -> StdResult<()> {
+        let admin_info = mock_info("admin", &[]);
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            instantiate_msg(admin_info),
+        )?;
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Snip721Migratable(Snip721MigratableQueryMsg::ContractVersion {}),
+        )?;
+
+        let answer: Snip721MigratableQueryAnswer = from_binary(&res)?;
+        let expected = Snip721MigratableQueryAnswer::ContractVersion(ContractVersion {
+            contract: "snip721-migratable".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        });
+        assert_eq!(expected, answer);
+        Ok(())
+    }
 }
@@ -1,23 +1,38 @@
 #[cfg(test)]
 mod tests {
-    use cosmwasm_std::testing::{mock_dependencies, mock_info};
+    use cosmwasm_std::testing::{mock_dependencies, mock_info, MockApi, MockQuerier, MockStorage};
     use cosmwasm_std::{
         from_binary, Addr, Api, Binary, BlockInfo, CanonicalAddr, Coin, ContractInfo, CosmosMsg,
-        Deps, DepsMut, Empty, Env, ReplyOn, StdResult, Timestamp, TransactionInfo, WasmMsg,
+        Deps, DepsMut, Env, OwnedDeps, Reply, ReplyOn, StdError, StdResult, SubMsgResult,
+        Timestamp, TransactionInfo, WasmMsg,
     };
     use cw_migratable_contract_std::execute::add_migration_complete_event_subscriber;
     use cw_migratable_contract_std::msg::{MigratableExecuteMsg, MigrationListenerExecuteMsg};
     use cw_migratable_contract_std::state::{
         canonicalize, CanonicalContractInfo, MIGRATION_COMPLETE_EVENT_SUBSCRIBERS,
+        REMAINING_MIGRATION_COMPLETE_EVENT_SUB_SLOTS,
     };
+    use migration_utils::migration_complete_notifications::{
+        MigrationCompleteNotificationFailure, MIGRATION_COMPLETE_NOTIFICATION_REPLY_ID_OFFSET,
+    };
+    use migration_utils::version::{ContractVersion, CONTRACT_VERSION};
     use secret_toolkit::permit::{
         validate, Permit, PermitParams, PermitSignature, PubKey, TokenPermissions,
     };
-    use snip721_reference_impl::state::{save, Config, CONFIG_KEY};
+    use snip721_reference_impl::msg::InstantiateConfig;
+    use snip721_reference_impl::state::{load, save, Config, CONFIG_KEY};
 
-    use crate::contract::{execute, instantiate, update_migrated_dependency};
+    use crate::contract::{execute, instantiate, query, reply, update_migrated_dependency};
     use crate::contract_migrate::migrate;
-    use crate::msg::ExecuteMsg;
+    use crate::msg::{
+        ExecuteMsg, MigrateMsg, QueryMsg, Snip721MigratableExecuteMsg,
+        Snip721MigratableQueryAnswer, Snip721MigratableQueryMsg,
+    };
+    use crate::state::{
+        MigrateInTokensProgress, MigrationCompleteEventSubscriptionPolicy, MigrationRecord,
+        MIGRATE_IN_TOKENS_PROGRESS,
+    };
+    use crate::state_upgrades::run_state_upgrades;
     use crate::test_utils::test_utils::instantiate_msg;
 
     const CONTRACT_ADDRESS_0: &str = "secret1rf03820fp8gngzg2w02vd30ns78qkc8rg8dxaq";
@@ -196,12 +211,15 @@ mod tests {
 
         let env_0_migrated = custom_mock_env_0_migrated();
 
-        let res = migrate(deps.as_mut(), env_0_migrated.clone(), Empty::default())?;
+        let res = migrate(deps.as_mut(), env_0_migrated.clone(), MigrateMsg::default())?;
 
         assert_eq!(2, res.messages.len());
-        for sub_msg in &res.messages {
-            assert_eq!(0, sub_msg.id);
-            assert_eq!(ReplyOn::Never, sub_msg.reply_on);
+        for (index, sub_msg) in res.messages.iter().enumerate() {
+            assert_eq!(
+                MIGRATION_COMPLETE_NOTIFICATION_REPLY_ID_OFFSET + index as u64,
+                sub_msg.id
+            );
+            assert_eq!(ReplyOn::Error, sub_msg.reply_on);
         }
         assert_is_migration_complete_notification_msg_to_contract(
             &res.messages[0].msg,
@@ -270,4 +288,906 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn migrate_with_max_migration_complete_event_subscribers_updates_remaining_slots(
+    ) -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        instantiate(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            instantiate_msg(admin_info.clone()),
+        )?;
+        execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            ExecuteMsg::Migrate(MigratableExecuteMsg::SubscribeToMigrationCompleteEvent {
+                address: "notify_0_address".to_string(),
+                code_hash: "notify_0_code_hash".to_string(),
+            }),
+        )?;
+
+        migrate(
+            deps.as_mut(),
+            custom_mock_env_0_migrated(),
+            MigrateMsg::V1 {
+                reason: None,
+                max_migration_complete_event_subscribers: Some(5),
+                config: None,
+                force: None,
+            },
+        )?;
+
+        let remaining_slots =
+            REMAINING_MIGRATION_COMPLETE_EVENT_SUB_SLOTS.load(deps.as_ref().storage)?;
+        assert_eq!(4, remaining_slots);
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_with_max_migration_complete_event_subscribers_below_subscriber_count_fails(
+    ) -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        instantiate(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            instantiate_msg(admin_info.clone()),
+        )?;
+        execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            ExecuteMsg::Migrate(MigratableExecuteMsg::SubscribeToMigrationCompleteEvent {
+                address: "notify_0_address".to_string(),
+                code_hash: "notify_0_code_hash".to_string(),
+            }),
+        )?;
+
+        let res = migrate(
+            deps.as_mut(),
+            custom_mock_env_0_migrated(),
+            MigrateMsg::V1 {
+                reason: None,
+                max_migration_complete_event_subscribers: Some(0),
+                config: None,
+                force: None,
+            },
+        );
+
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err(
+                "max_migration_complete_event_subscribers can not be less than the 1 contracts already subscribed"
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_with_config_overrides_only_set_flags() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        instantiate(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            instantiate_msg(admin_info.clone()),
+        )?;
+        let config_before: Config = load(deps.as_ref().storage, CONFIG_KEY)?;
+
+        let res = migrate(
+            deps.as_mut(),
+            custom_mock_env_0_migrated(),
+            MigrateMsg::V1 {
+                reason: Some("enable burn".to_string()),
+                max_migration_complete_event_subscribers: None,
+                config: Some(InstantiateConfig {
+                    public_token_supply: None,
+                    public_owner: None,
+                    enable_sealed_metadata: None,
+                    unwrapped_metadata_is_private: None,
+                    minter_may_update_metadata: None,
+                    owner_may_update_metadata: None,
+                    enable_burn: Some(!config_before.burn_is_enabled),
+                }),
+                force: None,
+            },
+        )?;
+
+        let config_after: Config = load(deps.as_ref().storage, CONFIG_KEY)?;
+        assert_eq!(!config_before.burn_is_enabled, config_after.burn_is_enabled);
+        assert_eq!(
+            config_before.token_supply_is_public,
+            config_after.token_supply_is_public
+        );
+        assert_eq!(config_before.owner_is_public, config_after.owner_is_public);
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "reason" && a.value == "enable burn"));
+        Ok(())
+    }
+
+    fn instantiate_with_stored_version(
+        contract: &str,
+        version: &str,
+    ) -> StdResult<OwnedDeps<MockStorage, MockApi, MockQuerier>> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        instantiate(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            instantiate_msg(admin_info),
+        )?;
+        CONTRACT_VERSION.save(
+            deps.as_mut().storage,
+            &ContractVersion {
+                contract: contract.to_string(),
+                version: version.to_string(),
+            },
+        )?;
+        Ok(deps)
+    }
+
+    #[test]
+    fn migrate_from_older_version_saves_current_version() -> StdResult<()> {
+        let mut deps = instantiate_with_stored_version("snip721-migratable", "2.0.0")?;
+
+        migrate(
+            deps.as_mut(),
+            custom_mock_env_0_migrated(),
+            MigrateMsg::default(),
+        )?;
+
+        assert_eq!(
+            ContractVersion {
+                contract: "snip721-migratable".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            CONTRACT_VERSION.load(deps.as_ref().storage)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_from_newer_version_fails_without_force() -> StdResult<()> {
+        let mut deps = instantiate_with_stored_version("snip721-migratable", "99.0.0")?;
+
+        let res = migrate(
+            deps.as_mut(),
+            custom_mock_env_0_migrated(),
+            MigrateMsg::default(),
+        );
+
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err(format!(
+                "Can not downgrade snip721-migratable from version 99.0.0 to {} without force",
+                env!("CARGO_PKG_VERSION")
+            ))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_from_newer_version_succeeds_with_force() -> StdResult<()> {
+        let mut deps = instantiate_with_stored_version("snip721-migratable", "99.0.0")?;
+
+        migrate(
+            deps.as_mut(),
+            custom_mock_env_0_migrated(),
+            MigrateMsg::V1 {
+                reason: None,
+                max_migration_complete_event_subscribers: None,
+                config: None,
+                force: Some(true),
+            },
+        )?;
+
+        assert_eq!(
+            env!("CARGO_PKG_VERSION"),
+            CONTRACT_VERSION.load(deps.as_ref().storage)?.version
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_from_different_contract_fails_without_force() -> StdResult<()> {
+        let mut deps = instantiate_with_stored_version("snip721-dealer", "1.0.0")?;
+
+        let res = migrate(
+            deps.as_mut(),
+            custom_mock_env_0_migrated(),
+            MigrateMsg::default(),
+        );
+
+        assert_eq!(
+            res.err().unwrap(),
+            StdError::generic_err(
+                "Can not migrate from contract snip721-dealer to snip721-migratable without force"
+            )
+        );
+        Ok(())
+    }
+
+    fn save_v2_migrate_in_tokens_progress(deps: DepsMut) -> StdResult<()> {
+        MIGRATE_IN_TOKENS_PROGRESS.save(
+            deps.storage,
+            &MigrateInTokensProgress {
+                migrate_in_mint_cnt: 10,
+                migrate_in_next_mint_index: 4,
+            },
+        )
+    }
+
+    #[test]
+    fn migrate_from_v2_removes_migrate_in_tokens_progress() -> StdResult<()> {
+        let mut deps = instantiate_with_stored_version("snip721-migratable", "2.0.0")?;
+        save_v2_migrate_in_tokens_progress(deps.as_mut())?;
+
+        let res = migrate(
+            deps.as_mut(),
+            custom_mock_env_0_migrated(),
+            MigrateMsg::default(),
+        )?;
+
+        assert_eq!(
+            None,
+            MIGRATE_IN_TOKENS_PROGRESS.may_load(deps.as_ref().storage)?
+        );
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "state_upgrade" && a.value == "3.0.0"));
+        Ok(())
+    }
+
+    #[test]
+    fn state_upgrades_only_run_steps_newer_than_stored_version() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        save_v2_migrate_in_tokens_progress(deps.as_mut())?;
+
+        let applied = run_state_upgrades(deps.as_mut().storage, Some("3.0.0"))?;
+
+        assert!(applied.is_empty());
+        assert!(MIGRATE_IN_TOKENS_PROGRESS
+            .may_load(deps.as_ref().storage)?
+            .is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn state_upgrades_run_every_step_when_version_is_unknown_and_are_idempotent() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        save_v2_migrate_in_tokens_progress(deps.as_mut())?;
+
+        let applied = run_state_upgrades(deps.as_mut().storage, None)?;
+        assert_eq!(vec!["3.0.0"], applied);
+        assert_eq!(
+            None,
+            MIGRATE_IN_TOKENS_PROGRESS.may_load(deps.as_ref().storage)?
+        );
+
+        let applied_again = run_state_upgrades(deps.as_mut().storage, None)?;
+        assert_eq!(applied, applied_again);
+        assert_eq!(
+            None,
+            MIGRATE_IN_TOKENS_PROGRESS.may_load(deps.as_ref().storage)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn failed_migration_complete_notification_reply_is_recorded_and_queryable() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        instantiate(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            instantiate_msg(admin_info),
+        )?;
+        let contracts_to_notify = vec![
+            CanonicalContractInfo {
+                address: deps
+                    .api
+                    .addr_canonicalize(Addr::unchecked("notify_0_address").as_str())?,
+                code_hash: "notify_0_code_hash".to_string(),
+            },
+            CanonicalContractInfo {
+                address: deps
+                    .api
+                    .addr_canonicalize(Addr::unchecked("notify_1_address").as_str())?,
+                code_hash: "notify_1_code_hash".to_string(),
+            },
+        ];
+        MIGRATION_COMPLETE_EVENT_SUBSCRIBERS.save(deps.as_mut().storage, &contracts_to_notify)?;
+        let env_0_migrated = custom_mock_env_0_migrated();
+        let res = migrate(deps.as_mut(), env_0_migrated.clone(), MigrateMsg::default())?;
+
+        reply(
+            deps.as_mut(),
+            env_0_migrated.clone(),
+            Reply {
+                id: res.messages[1].id,
+                result: SubMsgResult::Err("notify_1 is broken".to_string()),
+            },
+        )?;
+
+        let query_res = query(
+            deps.as_ref(),
+            env_0_migrated,
+            QueryMsg::Snip721Migratable(
+                Snip721MigratableQueryMsg::FailedMigrationCompleteNotifications {},
+            ),
+        )?;
+        let answer: Snip721MigratableQueryAnswer = from_binary(&query_res)?;
+        let expected = Snip721MigratableQueryAnswer::FailedMigrationCompleteNotifications {
+            failures: vec![MigrationCompleteNotificationFailure {
+                subscriber: contracts_to_notify[1].humanize(deps.as_ref().api)?,
+                error: "notify_1 is broken".to_string(),
+            }],
+        };
+        assert_eq!(expected, answer);
+        Ok(())
+    }
+
+    #[test]
+    fn failed_migration_complete_notification_reply_finds_subscriber_after_list_changes(
+    ) -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        instantiate(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            instantiate_msg(admin_info.clone()),
+        )?;
+        let contracts_to_notify = vec![
+            CanonicalContractInfo {
+                address: deps
+                    .api
+                    .addr_canonicalize(Addr::unchecked("notify_0_address").as_str())?,
+                code_hash: "notify_0_code_hash".to_string(),
+            },
+            CanonicalContractInfo {
+                address: deps
+                    .api
+                    .addr_canonicalize(Addr::unchecked("notify_1_address").as_str())?,
+                code_hash: "notify_1_code_hash".to_string(),
+            },
+        ];
+        MIGRATION_COMPLETE_EVENT_SUBSCRIBERS.save(deps.as_mut().storage, &contracts_to_notify)?;
+        let env_0_migrated = custom_mock_env_0_migrated();
+        let res = migrate(deps.as_mut(), env_0_migrated.clone(), MigrateMsg::default())?;
+        // notify_1 moves to index 0 before its notification's reply is handled
+        execute(
+            deps.as_mut(),
+            env_0_migrated.clone(),
+            admin_info,
+            ExecuteMsg::Snip721Migratable(
+                Snip721MigratableExecuteMsg::UnsubscribeFromMigrationCompleteEvent {
+                    address: "notify_0_address".to_string(),
+                },
+            ),
+        )?;
+
+        reply(
+            deps.as_mut(),
+            env_0_migrated.clone(),
+            Reply {
+                id: res.messages[1].id,
+                result: SubMsgResult::Err("notify_1 is broken".to_string()),
+            },
+        )?;
+        let unknown_res = reply(
+            deps.as_mut(),
+            env_0_migrated.clone(),
+            Reply {
+                id: MIGRATION_COMPLETE_NOTIFICATION_REPLY_ID_OFFSET + 5,
+                result: SubMsgResult::Err("unknown is broken".to_string()),
+            },
+        );
+
+        assert!(unknown_res.is_ok());
+        let query_res = query(
+            deps.as_ref(),
+            env_0_migrated,
+            QueryMsg::Snip721Migratable(
+                Snip721MigratableQueryMsg::FailedMigrationCompleteNotifications {},
+            ),
+        )?;
+        let answer: Snip721MigratableQueryAnswer = from_binary(&query_res)?;
+        let expected = Snip721MigratableQueryAnswer::FailedMigrationCompleteNotifications {
+            failures: vec![MigrationCompleteNotificationFailure {
+                subscriber: contracts_to_notify[1].humanize(deps.as_ref().api)?,
+                error: "notify_1 is broken".to_string(),
+            }],
+        };
+        assert_eq!(expected, answer);
+        Ok(())
+    }
+
+    #[test]
+    fn retry_failed_migration_complete_notifications_resends_only_failed() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        instantiate(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            instantiate_msg(admin_info.clone()),
+        )?;
+        let contracts_to_notify = vec![
+            CanonicalContractInfo {
+                address: deps
+                    .api
+                    .addr_canonicalize(Addr::unchecked("notify_0_address").as_str())?,
+                code_hash: "notify_0_code_hash".to_string(),
+            },
+            CanonicalContractInfo {
+                address: deps
+                    .api
+                    .addr_canonicalize(Addr::unchecked("notify_1_address").as_str())?,
+                code_hash: "notify_1_code_hash".to_string(),
+            },
+        ];
+        MIGRATION_COMPLETE_EVENT_SUBSCRIBERS.save(deps.as_mut().storage, &contracts_to_notify)?;
+        let env_0_migrated = custom_mock_env_0_migrated();
+        let res = migrate(deps.as_mut(), env_0_migrated.clone(), MigrateMsg::default())?;
+        reply(
+            deps.as_mut(),
+            env_0_migrated.clone(),
+            Reply {
+                id: res.messages[1].id,
+                result: SubMsgResult::Err("notify_1 is broken".to_string()),
+            },
+        )?;
+
+        let retry_msg = ExecuteMsg::Snip721Migratable(
+            Snip721MigratableExecuteMsg::RetryFailedMigrationCompleteNotifications {},
+        );
+        let res = execute(deps.as_mut(), env_0_migrated.clone(), admin_info, retry_msg)?;
+
+        assert_eq!(1, res.messages.len());
+        assert_eq!(
+            MIGRATION_COMPLETE_NOTIFICATION_REPLY_ID_OFFSET + 1,
+            res.messages[0].id
+        );
+        assert_eq!(ReplyOn::Error, res.messages[0].reply_on);
+        assert_is_migration_complete_notification_msg_to_contract(
+            &res.messages[0].msg,
+            &contracts_to_notify[1].humanize(deps.as_ref().api)?,
+            &env_0_migrated.contract,
+        );
+        let query_res = query(
+            deps.as_ref(),
+            env_0_migrated,
+            QueryMsg::Snip721Migratable(
+                Snip721MigratableQueryMsg::FailedMigrationCompleteNotifications {},
+            ),
+        )?;
+        let answer: Snip721MigratableQueryAnswer = from_binary(&query_res)?;
+        assert_eq!(
+            Snip721MigratableQueryAnswer::FailedMigrationCompleteNotifications { failures: vec![] },
+            answer
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn retry_failed_migration_complete_notifications_fails_for_non_admin() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        instantiate(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            instantiate_msg(admin_info),
+        )?;
+
+        let res = execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            mock_info("not_admin", &[]),
+            ExecuteMsg::Snip721Migratable(
+                Snip721MigratableExecuteMsg::RetryFailedMigrationCompleteNotifications {},
+            ),
+        );
+
+        assert_eq!(
+            Err(StdError::generic_err(
+                "This is an admin command and can only be run from the admin address",
+            )),
+            res
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn subscriber_can_unsubscribe_from_migration_complete_event_and_free_its_slot() -> StdResult<()>
+    {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        instantiate(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            instantiate_msg(admin_info),
+        )?;
+        let subscriber_info = mock_info("notify_0_address", &[]);
+        execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            subscriber_info.clone(),
+            ExecuteMsg::Migrate(MigratableExecuteMsg::SubscribeToMigrationCompleteEvent {
+                address: "notify_0_address".to_string(),
+                code_hash: "notify_0_code_hash".to_string(),
+            }),
+        )?;
+        assert_eq!(
+            0,
+            REMAINING_MIGRATION_COMPLETE_EVENT_SUB_SLOTS.load(deps.as_ref().storage)?
+        );
+
+        execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            subscriber_info,
+            ExecuteMsg::Snip721Migratable(
+                Snip721MigratableExecuteMsg::UnsubscribeFromMigrationCompleteEvent {
+                    address: "notify_0_address".to_string(),
+                },
+            ),
+        )?;
+
+        assert_eq!(
+            Vec::<CanonicalContractInfo>::new(),
+            MIGRATION_COMPLETE_EVENT_SUBSCRIBERS.load(deps.as_ref().storage)?
+        );
+        assert_eq!(
+            1,
+            REMAINING_MIGRATION_COMPLETE_EVENT_SUB_SLOTS.load(deps.as_ref().storage)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn only_subscriber_or_admin_can_unsubscribe_from_migration_complete_event() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        instantiate(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            instantiate_msg(admin_info.clone()),
+        )?;
+        execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            mock_info("notify_0_address", &[]),
+            ExecuteMsg::Migrate(MigratableExecuteMsg::SubscribeToMigrationCompleteEvent {
+                address: "notify_0_address".to_string(),
+                code_hash: "notify_0_code_hash".to_string(),
+            }),
+        )?;
+        let unsubscribe_msg = || {
+            ExecuteMsg::Snip721Migratable(
+                Snip721MigratableExecuteMsg::UnsubscribeFromMigrationCompleteEvent {
+                    address: "notify_0_address".to_string(),
+                },
+            )
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            mock_info("someone_else", &[]),
+            unsubscribe_msg(),
+        );
+        assert_eq!(
+            Err(StdError::generic_err(
+                "Only the subscriber or the admin can unsubscribe from migration complete events",
+            )),
+            res
+        );
+
+        execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            unsubscribe_msg(),
+        )?;
+        assert_eq!(
+            Vec::<CanonicalContractInfo>::new(),
+            MIGRATION_COMPLETE_EVENT_SUBSCRIBERS.load(deps.as_ref().storage)?
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info,
+            unsubscribe_msg(),
+        );
+        assert_eq!(
+            Err(StdError::generic_err(
+                "notify_0_address is not subscribed to migration complete events",
+            )),
+            res
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn admin_only_subscription_policy_rejects_non_admin_subscribers() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        let mut instantiate_msg = instantiate_msg(admin_info.clone());
+        instantiate_msg.migration_complete_event_subscription_policy =
+            Some(MigrationCompleteEventSubscriptionPolicy::AdminOnly);
+        instantiate(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            instantiate_msg,
+        )?;
+        let subscribe_msg = || {
+            ExecuteMsg::Migrate(MigratableExecuteMsg::SubscribeToMigrationCompleteEvent {
+                address: "notify_0_address".to_string(),
+                code_hash: "notify_0_code_hash".to_string(),
+            })
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            mock_info("notify_0_address", &[]),
+            subscribe_msg(),
+        );
+        assert_eq!(
+            Err(StdError::generic_err(
+                "Only the admin can subscribe contracts to migration complete events",
+            )),
+            res
+        );
+
+        execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info,
+            subscribe_msg(),
+        )?;
+        assert_eq!(
+            1,
+            MIGRATION_COMPLETE_EVENT_SUBSCRIBERS
+                .load(deps.as_ref().storage)?
+                .len()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn self_only_subscription_policy_rejects_subscribing_other_contracts() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        let mut instantiate_msg = instantiate_msg(admin_info.clone());
+        instantiate_msg.migration_complete_event_subscription_policy =
+            Some(MigrationCompleteEventSubscriptionPolicy::SelfOnly);
+        instantiate(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            instantiate_msg,
+        )?;
+        let subscribe_msg = || {
+            ExecuteMsg::Migrate(MigratableExecuteMsg::SubscribeToMigrationCompleteEvent {
+                address: "notify_0_address".to_string(),
+                code_hash: "notify_0_code_hash".to_string(),
+            })
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info,
+            subscribe_msg(),
+        );
+        assert_eq!(
+            Err(StdError::generic_err(
+                "A contract can only subscribe itself to migration complete events",
+            )),
+            res
+        );
+
+        execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            mock_info("notify_0_address", &[]),
+            subscribe_msg(),
+        )?;
+        assert_eq!(
+            1,
+            MIGRATION_COMPLETE_EVENT_SUBSCRIBERS
+                .load(deps.as_ref().storage)?
+                .len()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn migration_subscribers_and_remaining_slots_are_queryable() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        let mut instantiate_msg = instantiate_msg(admin_info.clone());
+        instantiate_msg.max_migration_complete_event_subscribers = 3;
+        instantiate(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            instantiate_msg,
+        )?;
+        for i in 0..2 {
+            execute(
+                deps.as_mut(),
+                custom_mock_env_0(),
+                admin_info.clone(),
+                ExecuteMsg::Migrate(MigratableExecuteMsg::SubscribeToMigrationCompleteEvent {
+                    address: format!("notify_{}_address", i),
+                    code_hash: format!("notify_{}_code_hash", i),
+                }),
+            )?;
+        }
+
+        let query_msg: QueryMsg = from_binary(&Binary::from(
+            br#"{"migration_subscribers":{"page":1,"page_size":1}}"#,
+        ))?;
+        let res = query(deps.as_ref(), custom_mock_env_0(), query_msg)?;
+        let answer: Snip721MigratableQueryAnswer = from_binary(&res)?;
+        let expected = Snip721MigratableQueryAnswer::MigrationSubscribers {
+            subscribers: vec![ContractInfo {
+                address: Addr::unchecked("notify_1_address"),
+                code_hash: "notify_1_code_hash".to_string(),
+            }],
+            count: 2,
+        };
+        assert_eq!(expected, answer);
+
+        let query_msg: QueryMsg =
+            from_binary(&Binary::from(br#"{"remaining_subscriber_slots":{}}"#))?;
+        let res = query(deps.as_ref(), custom_mock_env_0(), query_msg)?;
+        let answer: Snip721MigratableQueryAnswer = from_binary(&res)?;
+        assert_eq!(
+            Snip721MigratableQueryAnswer::RemainingSubscriberSlots { remaining: 1 },
+            answer
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn admin_can_set_max_migration_complete_event_subscribers() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        instantiate(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            instantiate_msg(admin_info.clone()),
+        )?;
+        execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            ExecuteMsg::Migrate(MigratableExecuteMsg::SubscribeToMigrationCompleteEvent {
+                address: "notify_0_address".to_string(),
+                code_hash: "notify_0_code_hash".to_string(),
+            }),
+        )?;
+        let set_max_msg = |max_migration_complete_event_subscribers| {
+            ExecuteMsg::Snip721Migratable(
+                Snip721MigratableExecuteMsg::SetMaxMigrationCompleteEventSubscribers {
+                    max_migration_complete_event_subscribers,
+                },
+            )
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            mock_info("not_admin", &[]),
+            set_max_msg(3),
+        );
+        assert_eq!(
+            Err(StdError::generic_err(
+                "This is an admin command and can only be run from the admin address",
+            )),
+            res
+        );
+
+        execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info.clone(),
+            set_max_msg(3),
+        )?;
+        assert_eq!(
+            2,
+            REMAINING_MIGRATION_COMPLETE_EVENT_SUB_SLOTS.load(deps.as_ref().storage)?
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            custom_mock_env_0(),
+            admin_info,
+            set_max_msg(0),
+        );
+        assert_eq!(
+            Err(StdError::generic_err(
+                "max_migration_complete_event_subscribers can not be less than the 1 contracts already subscribed",
+            )),
+            res
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_appends_queryable_migration_history() -> StdResult<()> {
+        let mut deps = mock_dependencies();
+        let admin_info = mock_info("admin", &[]);
+        let env_0 = custom_mock_env_0();
+        instantiate(
+            deps.as_mut(),
+            env_0.clone(),
+            admin_info.clone(),
+            instantiate_msg(admin_info),
+        )?;
+        let env_0_migrated = custom_mock_env_0_migrated();
+        migrate(
+            deps.as_mut(),
+            env_0_migrated.clone(),
+            MigrateMsg::V1 {
+                reason: Some("first".to_string()),
+                max_migration_complete_event_subscribers: None,
+                config: None,
+                force: None,
+            },
+        )?;
+        migrate(deps.as_mut(), env_0_migrated.clone(), MigrateMsg::default())?;
+
+        let res = query(
+            deps.as_ref(),
+            env_0_migrated.clone(),
+            QueryMsg::Snip721Migratable(Snip721MigratableQueryMsg::MigrationHistory {
+                page: 0,
+                page_size: 10,
+            }),
+        )?;
+        let answer: Snip721MigratableQueryAnswer = from_binary(&res)?;
+        let expected = Snip721MigratableQueryAnswer::MigrationHistory {
+            records: vec![
+                MigrationRecord {
+                    previous_code_hash: Some(env_0.contract.code_hash),
+                    new_code_hash: env_0_migrated.contract.code_hash.clone(),
+                    block_height: env_0_migrated.block.height,
+                    block_time: env_0_migrated.block.time,
+                    reason: Some("first".to_string()),
+                },
+                MigrationRecord {
+                    previous_code_hash: Some(env_0_migrated.contract.code_hash.clone()),
+                    new_code_hash: env_0_migrated.contract.code_hash.clone(),
+                    block_height: env_0_migrated.block.height,
+                    block_time: env_0_migrated.block.time,
+                    reason: None,
+                },
+            ],
+            count: 2,
+        };
+        assert_eq!(expected, answer);
+        Ok(())
+    }
 }
@@ -3,7 +3,7 @@ pub mod test_utils {
     use cosmwasm_std::MessageInfo;
     use snip721_reference_impl::msg::InstantiateMsg as Snip721InstantiateMsg;
 
-    use crate::msg::InstantiateMsg;
+    use crate::msg::{InstantiateMsg, MigrateMsg};
 
     pub fn instantiate_msg(admin_info: MessageInfo) -> InstantiateMsg {
         InstantiateMsg {
@@ -18,6 +18,18 @@ pub mod test_utils {
                 post_init_data: None,
             },
             max_migration_complete_event_subscribers: 1,
+            migration_complete_event_subscription_policy: None,
+        }
+    }
+
+    impl Default for MigrateMsg {
+        fn default() -> Self {
+            MigrateMsg::V1 {
+                reason: None,
+                max_migration_complete_event_subscribers: None,
+                config: None,
+                force: None,
+            }
         }
     }
 }
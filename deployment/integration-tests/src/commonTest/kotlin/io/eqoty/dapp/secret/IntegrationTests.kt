@@ -115,7 +115,7 @@ class IntegrationTests {
                 sender = senderAddress,
                 contractAddress = contract.address,
                 codeId = codeInfo.codeId.toInt(),
-                msg = "{}",
+                msg = """{"v1":{}}""",
                 codeHash = codeInfo.codeHash
             )
         )
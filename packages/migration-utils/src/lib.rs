@@ -0,0 +1,2 @@
+pub mod migration_complete_notifications;
+pub mod version;
@@ -0,0 +1,215 @@
+use cosmwasm_std::{
+    CanonicalAddr, ContractInfo, Deps, DepsMut, MessageInfo, Reply, ReplyOn, Response, StdError,
+    StdResult, Storage, SubMsg, SubMsgResult,
+};
+use cw_migratable_contract_std::execute::create_broadcast_migration_complete_notification_msgs;
+use cw_migratable_contract_std::state::{
+    CanonicalContractInfo, MIGRATION_COMPLETE_EVENT_SUBSCRIBERS,
+    REMAINING_MIGRATION_COMPLETE_EVENT_SUB_SLOTS,
+};
+use schemars::JsonSchema;
+use secret_toolkit::storage::{Item, Keymap};
+use serde::{Deserialize, Serialize};
+
+/// The reply id of a migration complete notification is this offset plus the index of the
+/// notified contract in MIGRATION_COMPLETE_EVENT_SUBSCRIBERS when the notification was sent
+pub const MIGRATION_COMPLETE_NOTIFICATION_REPLY_ID_OFFSET: u64 = 1 << 32;
+
+/// storage for the migration complete event subscribers that could not be notified
+pub static FAILED_MIGRATION_COMPLETE_NOTIFICATIONS: Item<Vec<FailedMigrationCompleteNotification>> =
+    Item::new(b"failedMigCompleteNotifs");
+/// storage for the subscriber each migration complete notification was sent to, by reply id
+pub static MIGRATION_COMPLETE_NOTIFICATION_RECIPIENTS: Keymap<u64, CanonicalContractInfo> =
+    Keymap::new(b"migCompleteNotifRecipients");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct FailedMigrationCompleteNotification {
+    /// the subscriber whose migration complete notification failed
+    pub subscriber: CanonicalContractInfo,
+    /// the error the notification failed with
+    pub error: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrationCompleteNotificationFailure {
+    /// the subscriber that could not be notified
+    pub subscriber: ContractInfo,
+    /// the error the notification failed with
+    pub error: String,
+}
+
+/// Creates a migration complete notification for every subscriber
+pub fn notify_migration_complete_event_subscribers(
+    deps: DepsMut,
+    migrated_to: &ContractInfo,
+) -> StdResult<Vec<SubMsg>> {
+    let contracts_to_notify = MIGRATION_COMPLETE_EVENT_SUBSCRIBERS
+        .load(deps.storage)
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .collect();
+    create_migration_complete_notification_msgs(deps, migrated_to, contracts_to_notify)
+}
+
+/// Re-sends the migration complete notification to every subscriber with a recorded failure. The
+/// failures are cleared first, so only a notification that fails again is recorded again.
+/// Failures of contracts that are no longer subscribed are dropped.
+pub fn retry_failed_migration_complete_notifications(
+    deps: DepsMut,
+    migrated_to: &ContractInfo,
+) -> StdResult<Vec<SubMsg>> {
+    let failed = FAILED_MIGRATION_COMPLETE_NOTIFICATIONS
+        .load(deps.storage)
+        .unwrap_or_default();
+    FAILED_MIGRATION_COMPLETE_NOTIFICATIONS.remove(deps.storage);
+    let contracts_to_notify = MIGRATION_COMPLETE_EVENT_SUBSCRIBERS
+        .load(deps.storage)
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .filter(|(_, subscriber)| {
+            failed
+                .iter()
+                .any(|f| f.subscriber.address == subscriber.address)
+        })
+        .collect();
+    create_migration_complete_notification_msgs(deps, migrated_to, contracts_to_notify)
+}
+
+/// Creates a migration complete notification for each (subscriber index, subscriber) pair. A
+/// notification that fails replies with its reply id instead of reverting the migration, and
+/// the subscriber it was sent to is saved under that reply id so the reply does not depend on
+/// the subscriber list staying unchanged.
+fn create_migration_complete_notification_msgs(
+    deps: DepsMut,
+    migrated_to: &ContractInfo,
+    contracts_to_notify: Vec<(usize, CanonicalContractInfo)>,
+) -> StdResult<Vec<SubMsg>> {
+    let mut msgs: Vec<SubMsg> = vec![];
+    for (index, contract_to_notify) in contracts_to_notify {
+        let reply_id = MIGRATION_COMPLETE_NOTIFICATION_REPLY_ID_OFFSET + index as u64;
+        let humanized = contract_to_notify.humanize(deps.api)?;
+        MIGRATION_COMPLETE_NOTIFICATION_RECIPIENTS.insert(
+            deps.storage,
+            &reply_id,
+            &contract_to_notify,
+        )?;
+        msgs.extend(create_broadcast_migration_complete_notification_msgs(
+            deps.as_ref(),
+            ReplyOn::Error,
+            reply_id,
+            migrated_to,
+            vec![humanized],
+            None,
+        )?);
+    }
+    Ok(msgs)
+}
+
+/// Records the subscriber whose migration complete notification failed. This never returns an
+/// error, because that would revert the migration or retry that sent the notification. A reply
+/// whose subscriber cannot be found is skipped.
+pub fn on_migration_complete_notification_reply(
+    deps: DepsMut,
+    reply: Reply,
+) -> StdResult<Response> {
+    let error = match reply.result {
+        SubMsgResult::Err(error) => error,
+        SubMsgResult::Ok(_) => return Ok(Response::new()),
+    };
+    let subscriber = match MIGRATION_COMPLETE_NOTIFICATION_RECIPIENTS.get(deps.storage, &reply.id) {
+        Some(subscriber) => subscriber,
+        None => {
+            return Ok(Response::new().add_attribute(
+                "failed_migration_complete_notification_unknown_reply_id",
+                reply.id.to_string(),
+            ))
+        }
+    };
+    let subscriber_address = match deps.api.addr_humanize(&subscriber.address) {
+        Ok(address) => address.to_string(),
+        Err(_) => subscriber.address.to_string(),
+    };
+    if save_failed_migration_complete_notification(deps.storage, subscriber, error).is_err() {
+        return Ok(Response::new().add_attribute(
+            "unrecorded_failed_migration_complete_notification",
+            subscriber_address,
+        ));
+    }
+    Ok(Response::new().add_attribute("failed_migration_complete_notification", subscriber_address))
+}
+
+/// Saves a failed notification, replacing any earlier failure recorded for the same subscriber
+fn save_failed_migration_complete_notification(
+    storage: &mut dyn Storage,
+    subscriber: CanonicalContractInfo,
+    error: String,
+) -> StdResult<()> {
+    let mut failed = FAILED_MIGRATION_COMPLETE_NOTIFICATIONS
+        .load(storage)
+        .unwrap_or_default();
+    failed.retain(|f| f.subscriber.address != subscriber.address);
+    failed.push(FailedMigrationCompleteNotification { subscriber, error });
+    FAILED_MIGRATION_COMPLETE_NOTIFICATIONS.save(storage, &failed)
+}
+
+/// Removes `address` from the migration complete event subscribers, freeing its subscriber slot
+/// and dropping any failed notification recorded for it. Only the subscriber itself or `admin`
+/// can unsubscribe it.
+pub fn unsubscribe_from_migration_complete_event(
+    deps: DepsMut,
+    info: &MessageInfo,
+    admin: &CanonicalAddr,
+    address: String,
+) -> StdResult<Response> {
+    let raw_address = deps
+        .api
+        .addr_canonicalize(deps.api.addr_validate(address.as_str())?.as_str())?;
+    let raw_sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if raw_sender != raw_address && &raw_sender != admin {
+        return Err(StdError::generic_err(
+            "Only the subscriber or the admin can unsubscribe from migration complete events",
+        ));
+    }
+    let mut subscribers = MIGRATION_COMPLETE_EVENT_SUBSCRIBERS
+        .load(deps.storage)
+        .unwrap_or_default();
+    let index = subscribers
+        .iter()
+        .position(|s| s.address == raw_address)
+        .ok_or_else(|| {
+            StdError::generic_err(format!(
+                "{} is not subscribed to migration complete events",
+                address
+            ))
+        })?;
+    subscribers.remove(index);
+    MIGRATION_COMPLETE_EVENT_SUBSCRIBERS.save(deps.storage, &subscribers)?;
+    if let Some(remaining) = REMAINING_MIGRATION_COMPLETE_EVENT_SUB_SLOTS.may_load(deps.storage)? {
+        REMAINING_MIGRATION_COMPLETE_EVENT_SUB_SLOTS.save(deps.storage, &(remaining + 1))?;
+    }
+    let mut failed = FAILED_MIGRATION_COMPLETE_NOTIFICATIONS
+        .load(deps.storage)
+        .unwrap_or_default();
+    failed.retain(|f| f.subscriber.address != raw_address);
+    FAILED_MIGRATION_COMPLETE_NOTIFICATIONS.save(deps.storage, &failed)?;
+    Ok(Response::new())
+}
+
+/// Loads the recorded failed migration complete notifications with humanized subscribers
+pub fn load_failed_migration_complete_notifications(
+    deps: Deps,
+) -> StdResult<Vec<MigrationCompleteNotificationFailure>> {
+    FAILED_MIGRATION_COMPLETE_NOTIFICATIONS
+        .load(deps.storage)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|f| {
+            Ok(MigrationCompleteNotificationFailure {
+                subscriber: f.subscriber.into_humanized(deps.api)?,
+                error: f.error,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,80 @@
+use cosmwasm_std::{StdError, StdResult, Storage};
+use schemars::JsonSchema;
+use secret_toolkit::storage::Item;
+use serde::{Deserialize, Serialize};
+
+/// storage for the name and version of the code that last wrote this contract's storage
+pub static CONTRACT_VERSION: Item<ContractVersion> = Item::new(b"contractVersion");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractVersion {
+    /// the name of the contract
+    pub contract: String,
+    /// the semver of the contract
+    pub version: String,
+}
+
+/// Saves `contract` and `version` as the name and version of the code that last wrote the
+/// contract's storage
+pub fn save_contract_version(
+    storage: &mut dyn Storage,
+    contract: &str,
+    version: &str,
+) -> StdResult<()> {
+    CONTRACT_VERSION.save(
+        storage,
+        &ContractVersion {
+            contract: contract.to_string(),
+            version: version.to_string(),
+        },
+    )
+}
+
+/// Returns an error if the stored contract version belongs to a contract other than `contract` or
+/// is newer than `version`, unless `force` is set. Contracts instantiated before versions were
+/// tracked have no stored version and can always be migrated.
+pub fn assert_can_migrate(
+    storage: &dyn Storage,
+    contract: &str,
+    version: &str,
+    force: bool,
+) -> StdResult<()> {
+    if force {
+        return Ok(());
+    }
+    let stored = match CONTRACT_VERSION.may_load(storage)? {
+        Some(stored) => stored,
+        None => return Ok(()),
+    };
+    if stored.contract != contract {
+        return Err(StdError::generic_err(format!(
+            "Can not migrate from contract {} to {} without force",
+            stored.contract, contract
+        )));
+    }
+    if parse_semver(&stored.version)? > parse_semver(version)? {
+        return Err(StdError::generic_err(format!(
+            "Can not downgrade {} from version {} to {} without force",
+            contract, stored.version, version
+        )));
+    }
+    Ok(())
+}
+
+/// Parses the major, minor and patch numbers of a semver, ignoring any pre-release or build
+/// metadata
+pub fn parse_semver(version: &str) -> StdResult<(u64, u64, u64)> {
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    let parts = core
+        .split('.')
+        .map(|p| p.parse::<u64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| StdError::generic_err(format!("Invalid contract version: {}", version)))?;
+    match parts[..] {
+        [major, minor, patch] => Ok((major, minor, patch)),
+        _ => Err(StdError::generic_err(format!(
+            "Invalid contract version: {}",
+            version
+        ))),
+    }
+}